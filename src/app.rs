@@ -1,11 +1,16 @@
 pub(crate) mod engine {
-    use crate::data::data_types::{AppState, AppConfig};
+    use crate::data::{data_helpers, data_types::{Alert, AppState, AppConfig, AppError, AuthMethod, Registry, RegistryNode, Connection, SecretSource, ServerProfile, Snapshot, SyncEvent, SyncPhase}};
+    use chrono::Local;
     use clap::{Arg, Command, ArgMatches, crate_authors, crate_description, crate_version, ArgAction};
-    use std::{fs, path::PathBuf};
+    use crossterm::{
+        event::{self, Event, KeyCode, KeyModifiers},
+        terminal::{disable_raw_mode, enable_raw_mode},
+    };
+    use std::{fs, io::{self, Write}, path::PathBuf, time::Instant};
 
 
-    /// This Enum represents all possible outcomes from checks
     /*
+    /// This Enum represents all possible outcomes from checks
     enum InitializerStatus {
         Passed,
         Failed,
@@ -25,45 +30,236 @@ pub(crate) mod engine {
     */
 
 
+    /// Swaps the selected node with the one above it inside the same directory
     pub fn switch_up(state: &mut AppState) {
-        if let Some(i) = state.list_state.selected() {
+        if let Some(mut path) = state.get_selected_path() {
+            let i: usize = path.pop().unwrap_or(0);
             if i > 0 {
-                state.get_elements_mut().swap(i, i-1);
+                let snapshot: Snapshot = state.snapshot(format!("move of '{}'", state.selected_title()));
+                if let Some(content) = state.get_content_mut(&path) {
+                    content.swap(i, i-1);
+                    Registry::number_positions(content);
+                    path.push(i-1);
+                    state.select_path(&path);
+                    state.record(snapshot);
+                    state.unsynced();
+                }
             }
-
         };
     }
 
+    /// Swaps the selected node with the one below it inside the same directory
     pub fn switch_down(state: &mut AppState) {
-        if let Some(i) = state.list_state.selected() {
-            if i < state.get_elements().len()-1 {
-                state.get_elements_mut().swap(i, i+1);
+        if let Some(mut path) = state.get_selected_path() {
+            let i: usize = path.pop().unwrap_or(0);
+            let snapshot: Snapshot = state.snapshot(format!("move of '{}'", state.selected_title()));
+            if let Some(content) = state.get_content_mut(&path) {
+                if i+1 < content.len() {
+                    content.swap(i, i+1);
+                    Registry::number_positions(content);
+                    path.push(i+1);
+                    state.select_path(&path);
+                    state.record(snapshot);
+                    state.unsynced();
+                }
             }
+        };
+    }
 
+    /// Moves the selected node out of its directory, placing it right after
+    /// the directory it was located in
+    pub fn move_out(state: &mut AppState) {
+        if let Some(mut path) = state.get_selected_path() {
+            let i: usize = path.pop().unwrap_or(0);
+            let Some(parent_i) = path.pop() else {
+                return;
+            };
+            let snapshot: Snapshot = state.snapshot(format!("move of '{}'", state.selected_title()));
+            if let Some(content) = state.get_content_mut(&path) {
+                if let Some(RegistryNode::Directory(directory)) = content.get_mut(parent_i) {
+                    let mut node: RegistryNode = directory.content_mut().remove(i);
+                    Registry::number_positions(directory.content_mut());
+                    node.set_moved(true);
+                    content.insert(parent_i+1, node);
+                    Registry::number_positions(content);
+                    path.push(parent_i+1);
+                    state.select_path(&path);
+                    state.record(snapshot);
+                    state.unsynced();
+                }
+            }
+        };
+    }
+
+    /// Moves the selected node into the directory directly above it, placing
+    /// it at the end of that directory
+    pub fn move_in(state: &mut AppState) {
+        if let Some(mut path) = state.get_selected_path() {
+            let i: usize = path.pop().unwrap_or(0);
+            if i == 0 {
+                return;
+            }
+            let snapshot: Snapshot = state.snapshot(format!("move of '{}'", state.selected_title()));
+            if let Some(content) = state.get_content_mut(&path) {
+                if !content.get(i-1).is_some_and(|n| n.directory().is_some() && !n.removed()) {
+                    return;
+                }
+                let mut node: RegistryNode = content.remove(i);
+                if let Some(RegistryNode::Directory(directory)) = content.get_mut(i-1) {
+                    node.set_moved(true);
+                    directory.expand();
+                    directory.content_mut().push(node);
+                    Registry::number_positions(directory.content_mut());
+                    let len: usize = directory.content().len();
+                    path.append(&mut vec![i-1, len-1]);
+                    state.select_path(&path);
+                    state.record(snapshot);
+                    state.unsynced();
+                }
+            }
         };
     }
 
     /// Read the app configuration
-    fn obtain_app_config() -> Option<AppConfig> {
-        let mut path = dirs::config_dir().unwrap_or(PathBuf::new());
+    fn obtain_app_config() -> Result<AppConfig, AppError> {
+        let mut path = dirs::config_dir().unwrap_or_default();
         path.push("freemind/");
         fs::create_dir_all(path.clone()).ok();
         path.push("freemind-cli.config");
-        confy::load_path(path).ok()
+        confy::load_path(path).map_err(|e| AppError::Config(e.to_string()))
+    }
+
+    /// Saves the app configuration unless it was not loaded from the file
+    pub fn save_app_config(config: &AppConfig) -> Result<(), AppError> {
+        match config.read_only {
+            true => Ok(()),
+            false => write_app_config(config),
+        }
     }
 
     /// Save the app configuration
-    fn write_app_config(config: &AppConfig) -> Option<()> {
-        let mut path = dirs::config_dir().unwrap_or(PathBuf::new());
+    fn write_app_config(config: &AppConfig) -> Result<(), AppError> {
+        let mut path = dirs::config_dir().unwrap_or_default();
         path.push("freemind/");
         fs::create_dir_all(path.clone()).ok();
         path.push("freemind-cli.config");
-        confy::store_path(path, config).ok();
+        confy::store_path(path, config).map_err(|e| AppError::Config(e.to_string()))
+    }
+
+    /// Starts a sync in the background by fetching the registry, does nothing
+    /// if a sync is already running
+    pub fn start_sync(state: &mut AppState) -> Result<(), AppError> {
+        if state.get_sync_phase().is_some() {
+            return Ok(());
+        }
+        let connection: Connection = state.connection()?;
+        state.set_sync_task(
+            SyncPhase::Fetching,
+            tokio::spawn(async move { SyncEvent::Fetched(connection.fetch().await) })
+        );
+        Ok(())
+    }
+
+    /// Uploads the merged registry in the background
+    fn start_upload(state: &mut AppState, payload: String) -> Result<(), AppError> {
+        let connection: Connection = state.connection()?;
+        state.set_sync_task(
+            SyncPhase::Uploading,
+            tokio::spawn(async move { SyncEvent::Uploaded(connection.upload(payload).await) })
+        );
+        Ok(())
+    }
+
+    /// Advances the running sync once its current phase is finished in the
+    /// background. Returns the result as soon as the whole sync is finished
+    pub async fn poll_sync(state: &mut AppState) -> Option<Result<(), AppError>> {
+        let task = state.take_finished_sync_task()?;
+        let result: Result<bool, AppError> = match task.await {
+            Ok(SyncEvent::Fetched(Ok(xml))) => {
+                match state.merge_fetched(&xml) {
+                    Ok(Some(payload)) => start_upload(state, payload).map(|_| false),
+                    Ok(None) => Ok(true),
+                    Err(e) => Err(e),
+                }
+            },
+            Ok(SyncEvent::Uploaded(Ok(_))) => Ok(true),
+            Ok(SyncEvent::Fetched(Err(e))) | Ok(SyncEvent::Uploaded(Err(e))) => Err(e),
+            // The task was cancelled
+            Err(_) => {
+                state.cancel_sync();
+                return None;
+            },
+        };
+        match result {
+            Ok(true) => {
+                state.finish_sync();
+                Some(Ok(()))
+            },
+            Ok(false) => None,
+            Err(e) => {
+                state.cancel_sync();
+                Some(Err(e))
+            },
+        }
+    }
+
+    /// Returns the path of the offline cache file of the given profile
+    fn cache_path(profile: &str, name: &str) -> PathBuf {
+        let mut path = dirs::config_dir().unwrap_or_default();
+        path.push("freemind/");
+        fs::create_dir_all(path.clone()).ok();
+        match profile {
+            crate::DEFAULT_PROFILE_NAME => path.push(format!("{}.cache", name)),
+            profile => path.push(format!("{}-{}.cache", name, profile)),
+        }
+        path
+    }
+
+    /// Restores the registry and all unsynced changes from the offline cache
+    /// of the given name
+    pub fn load_cache(state: &mut AppState, name: &str) -> Option<()> {
+        let cache: String = fs::read_to_string(cache_path(state.profile_name(), name)).ok()?;
+        state.load_cache(&cache).ok()
+    }
+
+    /// Saves the registry and all unsynced changes to the offline cache of
+    /// the given name
+    pub fn write_cache(state: &AppState, name: &str) -> Option<()> {
+        let cache: String = state.to_cache().ok()?;
+        fs::write(cache_path(state.profile_name(), name), cache).ok()
+    }
+
+    /// Restores which alerts were dismissed
+    pub fn load_alerts(state: &mut AppState) -> Option<()> {
+        let dismissed: String = fs::read_to_string(cache_path(state.profile_name(), "alerts")).ok()?;
+        state.load_dismissed(&dismissed);
         Some(())
     }
 
+    /// Saves which alerts were dismissed, so they don't fire again. Alerts
+    /// dismissed by another instance in the meantime are read first to be kept
+    pub fn write_alerts(state: &mut AppState) -> Option<()> {
+        load_alerts(state);
+        fs::write(cache_path(state.profile_name(), "alerts"), state.dismissed_to_string()).ok()
+    }
+
+    /// Stops any running sync, saves the cache and replaces the state with
+    /// one for the next profile from the configuration
+    pub fn switch_profile(state: &mut AppState) -> String {
+        state.cancel_sync();
+        write_cache(state, crate::REGISTRY_CACHE_NAME);
+        write_alerts(state);
+
+        let mut config: AppConfig = state.config().clone();
+        config.select_profile(&config.next_profile_name());
+        *state = AppState::new(config);
+        load_cache(state, crate::REGISTRY_CACHE_NAME);
+        load_alerts(state);
+        state.profile_name().to_string()
+    }
+
     /// Obtains the runtime params
-    fn get_app_config() -> AppConfig {
+    async fn get_app_config() -> AppConfig {
         let args: ArgMatches = Command::new("Freemind TUI")
             .author(crate_authors!("\n"))
             .about(crate_description!())
@@ -75,51 +271,333 @@ pub(crate) mod engine {
                 .action(ArgAction::SetTrue)
                 .help("Enter the configuration setup")
             )
+            .arg(Arg::new("profile")
+                .short('p')
+                .long("profile")
+                .value_name("NAME")
+                .help("Use the server profile of the given name")
+            )
             .arg(Arg::new("skip-config-load")
                 .long("skip-config-load")
                 .action(ArgAction::SetTrue)
                 .help("Skip loading and saving of the configuration file")
             )
+            .subcommand(Command::new("daemon")
+                .about("Run without a terminal UI, syncing periodically and delivering alerts through the alert hook")
+            )
             .get_matches();
 
         let config_setup: &bool = args.get_one("config").unwrap_or(&false);
         let config_skip: &bool = args.get_one("skip-config-load").unwrap_or(&false);
+        let daemon: bool = args.subcommand_matches("daemon").is_some();
         let mut config: AppConfig = AppConfig::empty();
+        config.read_only = *config_skip;
         if !config_skip {
-            config = obtain_app_config()
-                .expect("FATAL! Failed to create or read config! (tried under '~/.config/freemind/freemind-cli.config')\nRun with `--skip-config-load` to avoid this issue, or fix your file permissions!");
+            config = match obtain_app_config() {
+                Ok(c) => c,
+                Err(e) => {
+                    eprintln!("FATAL! Failed to create or read config! (tried under '~/.config/freemind/freemind-cli.config')\n{}\nRun with `--skip-config-load` to avoid this issue, or fix your file permissions!", e);
+                    std::process::exit(1);
+                }
+            };
         }
 
+        if let Some(profile) = args.get_one::<String>("profile") {
+            if !config.select_profile(profile) {
+                eprintln!("FATAL! There is no profile named '{}'!\nAvailable profiles: {}", profile, config.profile_names().join(", "));
+                std::process::exit(1);
+            }
+        }
+
+        if config.active().stores_secret() && !config_setup {
+            eprintln!("{}", crate::WARN_MSG_PLAINTEXT_SECRET);
+        }
+
+        if daemon && (config.is_default() || config.is_empty()) {
+            eprintln!("FATAL! The daemon needs a configuration!\nRun with `--config` first to enter it.");
+            std::process::exit(1);
+        }
+        config.daemon = daemon;
+
         if *config_setup || config.is_default() || config.is_empty() {
-            println!("Config could not be read, found or was skipped.\nMake sure to enter your configuration!");
+            if !config_setup {
+                println!("Config could not be read, found or was skipped.\nMake sure to enter your configuration!");
+            }
+            config = setup_app_config(config, !config_skip).await;
         }
 
         config
     }
 
-    /// Initialize the app
-    pub fn init() -> AppConfig {
-        println!("Initializing...");
-        let config = get_app_config();
+    /// Prints the label and reads a line from the command line, returns the
+    /// default if the line is left empty
+    fn prompt_line(label: &str, default: &str) -> String {
+        match default.is_empty() {
+            true => print!("{}: ", label),
+            false => print!("{} [{}]: ", label, default),
+        }
+        io::stdout().flush().ok();
+
+        let mut input: String = String::new();
+        io::stdin().read_line(&mut input).ok();
+        match input.trim() {
+            "" => default.to_string(),
+            i => i.to_string(),
+        }
+    }
+
+    /// Prints the label and reads a line from the command line without showing
+    /// the input, returns the default if the line is left empty
+    fn prompt_secret(label: &str, default: &str) -> String {
+        match default.is_empty() {
+            true => print!("{}: ", label),
+            false => print!("{} [{}]: ", label, "*".repeat(default.len())),
+        }
+        io::stdout().flush().ok();
+
+        let mut input: String = String::new();
+        if enable_raw_mode().is_ok() {
+            while let Ok(Event::Key(key)) = event::read() {
+                match key.code {
+                    KeyCode::Enter | KeyCode::Esc => break,
+                    KeyCode::Char('c') if key.modifiers == KeyModifiers::CONTROL => break,
+                    KeyCode::Backspace => {
+                        input.pop();
+                    },
+                    KeyCode::Char(c) => input.push(c),
+                    _ => (),
+                }
+            }
+            disable_raw_mode().ok();
+        }
+        println!();
+
+        match input.trim() {
+            "" => default.to_string(),
+            i => i.to_string(),
+        }
+    }
+
+    /// Moves the secret stored in plain text in the config file to the new
+    /// source where possible, otherwise tells how to provide it there
+    fn migrate_secret(secret: &str, source: &SecretSource, secret_ref: &str) {
+        match source {
+            SecretSource::File if prompt_line(&format!("Move the secret from the config file to '{}'? [y/n]", secret_ref), "y") == "y" => {
+                match data_helpers::write_secret_file(secret_ref, secret) {
+                    Ok(_) => println!("The secret was written to '{}'", secret_ref),
+                    Err(e) => println!("{}", e),
+                }
+            },
+            SecretSource::Env => println!("The secret is removed from the config file, make sure '{}' is set before starting", secret_ref),
+            _ => println!("The secret is removed from the config file, make sure it can be obtained from the {} instead", source.to_string().to_lowercase()),
+        }
+    }
+
+    /// Tests the configuration by fetching the registry from the server
+    async fn test_app_config(config: &AppConfig) -> Result<(), AppError> {
+        let connection: Connection = AppState::new(config.clone()).connection()?;
+        connection.fetch().await?;
+        Ok(())
+    }
+
+    /// Interactively asks for the configuration on the command line, tests it
+    /// against the server and saves it if 'save' is true
+    async fn setup_app_config(current: AppConfig, save: bool) -> AppConfig {
+        println!("Setting up the configuration, leave a field empty to keep the value in brackets");
+        let mut config: AppConfig = current;
+        if config.is_default() {
+            *config.active_mut() = ServerProfile::empty();
+        }
+        println!("Profile: {}", config.active_profile_name());
+        if config.active().stores_secret() {
+            println!("The secret is stored in plain text, choose another source to move it out of the config file");
+        }
+
+        loop {
+            let server_address: String = prompt_line("Server address", &config.active().server_address);
+            let username: String = prompt_line("Username", &config.active().username);
+            // Storing the secret in the config file is only offered, not suggested
+            let current_source: &str = match config.active().secret_source {
+                SecretSource::Command => "2",
+                SecretSource::File => "3",
+                _ => "1",
+            };
+            let secret_source: SecretSource = SecretSource::from(
+                prompt_line("Secret source ([0] Config file in plain text, [1] Environment variable, [2] Command, [3] File)", current_source)
+                    .parse::<usize>()
+                    .unwrap_or(1)
+            );
+            let (secret, secret_ref): (String, String) = match secret_source {
+                SecretSource::Config => {
+                    println!("{}", crate::WARN_MSG_PLAINTEXT_SECRET);
+                    (prompt_secret("Token / Secret", &config.active().secret), "".to_string())
+                },
+                // Only keep the previous reference if it has the same meaning
+                _ => {
+                    let current_ref: &str = match config.active().secret_source == secret_source {
+                        true => &config.active().secret_ref,
+                        false => "",
+                    };
+                    let label: &str = match secret_source {
+                        SecretSource::Command => "Command printing the secret",
+                        SecretSource::File => "File containing the secret",
+                        _ => "Environment variable",
+                    };
+                    let secret_ref: String = prompt_line(label, current_ref);
+                    if config.active().stores_secret() {
+                        migrate_secret(&config.active().secret, &secret_source, &secret_ref);
+                    }
+                    ("".to_string(), secret_ref)
+                },
+            };
+            let auth_method: AuthMethod = AuthMethod::from(
+                prompt_line("Authentication method ([0] Token, [1] Password)", "0")
+                    .parse::<usize>()
+                    .unwrap_or(0)
+            );
+
+            *config.active_mut() = ServerProfile::new(server_address, username, secret, secret_ref, secret_source, auth_method);
+
+            println!("Testing the configuration...");
+            match test_app_config(&config).await {
+                Ok(_) => {
+                    println!("Successfully fetched the registry!");
+                    break;
+                },
+                Err(e) => {
+                    println!("Failed: {}", e);
+                    match prompt_line("[r]etry, [s]ave anyway or [a]bort", "r").as_str() {
+                        "s" => break,
+                        "a" => std::process::exit(1),
+                        _ => (),
+                    }
+                }
+            }
+        }
+
+        if save {
+            match write_app_config(&config) {
+                Ok(_) => println!("Configuration saved!"),
+                Err(e) => println!("{}", e),
+            }
+        }
 
         config
     }
+
+    /// Writes the message to stdout, prefixed with the current time
+    fn log(message: &str) {
+        println!("[{}] {}", Local::now().format("%Y-%m-%d %H:%M:%S"), message);
+    }
+
+    /// Logs the alert and runs the alert hook for it if one is configured
+    fn deliver_alert(config: &AppConfig, alert: &Alert) {
+        let due: String = alert.due_time().map(|d| d.to_rfc3339()).unwrap_or_default();
+        let location: String = alert.location.clone().unwrap_or_default();
+        log(&format!("Alert: {}", alert.to_string().replace('\n', ", ")));
+
+        if config.alert_hook.is_empty() {
+            return;
+        }
+        let id: String = alert.id.to_string();
+        let result: Result<(), String> = data_helpers::run_hook(
+            &config.alert_hook,
+            &[&alert.title, &due, &location],
+            &[
+                ("FREEMIND_ID", &id),
+                ("FREEMIND_TITLE", &alert.title),
+                ("FREEMIND_DUE", &due),
+                ("FREEMIND_LOCATION", &location),
+            ]
+        );
+        if let Err(e) = result {
+            log(&format!("Alert hook failed: {}", e));
+        }
+    }
+
+    /// Runs without a terminal UI until interrupted. Syncs periodically and
+    /// delivers every pending alert once, marking it as dismissed. The daemon
+    /// keeps its own offline cache, so it never overwrites unsynced changes
+    /// of the TUI, while dismissed alerts are shared with it
+    pub async fn run_daemon(config: AppConfig) {
+        let mut state: AppState = AppState::new(config);
+        load_cache(&mut state, crate::DAEMON_CACHE_NAME);
+        load_alerts(&mut state);
+        log(&format!("Daemon started for profile '{}'", state.profile_name()));
+
+        if let Err(e) = start_sync(&mut state) {
+            log(&format!("Sync failed: {}", e));
+        }
+        let mut last_sync: Instant = Instant::now();
+
+        loop {
+            tokio::select! {
+                _ = tokio::signal::ctrl_c() => break,
+                _ = tokio::time::sleep(crate::ALERT_CHECK_RATE) => (),
+            }
+
+            if let Some(result) = poll_sync(&mut state).await {
+                match result {
+                    Ok(_) if !state.get_conflicts().is_empty() => log(&format!(
+                        "Synced {} entries, {} conflicts need to be merged in the TUI",
+                        state.entry_count(),
+                        state.get_conflicts().len()
+                    )),
+                    Ok(_) => log(&format!("Synced {} entries", state.entry_count())),
+                    Err(e) => log(&format!("Sync failed: {}", e)),
+                }
+                write_cache(&state, crate::DAEMON_CACHE_NAME);
+            }
+
+            let sync_due: bool = state
+                .sync_interval()
+                .is_some_and(|interval| last_sync.elapsed() >= interval);
+            if sync_due {
+                if let Err(e) = start_sync(&mut state) {
+                    log(&format!("Sync failed: {}", e));
+                }
+                last_sync = Instant::now();
+            }
+
+            load_alerts(&mut state);
+            while state.check_alerts() {
+                if let Some(alert) = state.get_alert() {
+                    deliver_alert(state.config(), alert);
+                }
+                state.dismiss_alert();
+                write_alerts(&mut state);
+            }
+        }
+
+        state.cancel_sync();
+        write_cache(&state, crate::DAEMON_CACHE_NAME);
+        write_alerts(&mut state);
+        log("Daemon stopped");
+    }
+
+    /// Initialize the app
+    pub async fn init() -> AppConfig {
+        println!("Initializing...");
+        get_app_config().await
+    }
 }
 pub(crate) mod ui {
     use std::str::FromStr;
 
-    use crate::{AppState, AppCommand, data::data_types::AppFocus};
+    use crate::{AppState, AppCommand, data::{data_helpers, data_types::{AppFocus, AppLayout, EntryNode, RegistryNode}}};
     use cron::Schedule;
-    use chrono::{TimeZone, Utc, LocalResult, Local};
+    use chrono::{TimeZone, Utc, LocalResult, Local, NaiveDate, Datelike, Months, DateTime};
     use clap::{crate_name, crate_version};
     use tui::{
         style::Style,
         backend::{Backend},
         layout::{Constraint, Direction, Layout, Alignment, Rect},
-        widgets::{Block, Borders, Paragraph, Wrap, List, ListItem, BorderType, Row, Table},
-        Frame, text::{Spans, Span}, style::{Color, Modifier}, 
+        widgets::{Block, Borders, Paragraph, Wrap, List, ListItem, BorderType, Row, Table, Tabs, Cell},
+        Frame, text::{Spans, Span, Text}, style::{Color, Modifier}, 
     };
 
+    const SPINNER_FRAMES: [&str; 8] = ["⠋", "⠙", "⠸", "⠴", "⠦", "⠇", "⠏", "⠹"];
+
     /// Takes a timestamp and converts it to a Human Readable string in the current
     /// timezone
     fn display_timestamp(timestamp: i64) -> String {
@@ -131,13 +609,82 @@ pub(crate) mod ui {
         due
     }
 
+    /// Parses a color name or a #RRGGBB hex code as given in the color attribute
+    fn parse_color(value: &str) -> Option<Color> {
+        let value: String = value.trim().to_lowercase();
+        if let Some(hex) = value.strip_prefix('#') {
+            if hex.len() != 6 {
+                return None;
+            }
+            let channel = |i: usize| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok();
+            return Some(Color::Rgb(channel(0)?, channel(2)?, channel(4)?));
+        }
+        match value.as_str() {
+            "black" => Some(Color::Black),
+            "red" => Some(Color::Red),
+            "green" => Some(Color::Green),
+            "yellow" => Some(Color::Yellow),
+            "blue" => Some(Color::Blue),
+            "magenta" | "purple" => Some(Color::Magenta),
+            "cyan" => Some(Color::Cyan),
+            "gray" | "grey" => Some(Color::Gray),
+            "white" => Some(Color::White),
+            _ => None,
+        }
+    }
+
+    /// Returns the style of an entry drawn in the calendar, completed entries
+    /// are dimmed
+    fn entry_style(entry: &EntryNode) -> Style {
+        let style: Style = match entry.color().as_deref().and_then(parse_color) {
+            Some(color) => Style::default().fg(color),
+            None => Style::default(),
+        };
+        match entry.is_done() {
+            true => style.add_modifier(Modifier::DIM),
+            false => style,
+        }
+    }
+
+    /// Returns the text with every occurence of the searched texts highlighted
+    fn highlight_matches<'a>(text: &str, searched: &[&str]) -> Text<'a> {
+        let style: Style = Style::default()
+            .fg(Color::Rgb(0xFF, 0x2A, 0x6D))
+            .add_modifier(Modifier::BOLD | Modifier::UNDERLINED);
+
+        let lines: Vec<Spans<'a>> = text
+            .split('\n')
+            .map(|line| {
+                let mut ranges: Vec<(usize, usize)> = searched
+                    .iter()
+                    .flat_map(|s| data_helpers::find_matches(line, s))
+                    .collect();
+                ranges.sort();
+
+                let mut spans: Vec<Span<'a>> = Vec::new();
+                let mut last: usize = 0;
+                ranges
+                    .into_iter()
+                    .for_each(|(start, end)| {
+                        // Overlapping matches are highlighted as one
+                        let start: usize = start.max(last);
+                        if start >= end {
+                            return;
+                        }
+                        spans.push(Span::raw(line[last..start].to_string()));
+                        spans.push(Span::styled(line[start..end].to_string(), style));
+                        last = end;
+                    });
+                spans.push(Span::raw(line[last..].to_string()));
+                Spans::from(spans)
+            })
+            .collect();
+        Text::from(lines)
+    }
+
     /// Returns the value of the currently selected attribute of node
     pub fn get_selected_value(state: &AppState) -> Option<String> {
-        if let Some(node) = state.get_selected_attribute() {
-            Some(node.1)
-        } else {
-            None
-        }
+        state.get_selected_attribute().map(|node| node.1)
     }
 
     /// Returns a Hashmap that maps different representations of the value
@@ -149,7 +696,7 @@ pub(crate) mod ui {
 
             let value: String = node.1;
 
-            if let Some(num_val) = value.parse::<i64>().ok() {
+            if let Ok(num_val) = value.parse::<i64>() {
                 let due = display_timestamp(num_val);
                 cnt.push(("As UNIX Timestamp".to_string(), due));
             }
@@ -199,9 +746,14 @@ pub(crate) mod ui {
     
         //f.render_widget(Clear, the_chunk);
     
+        let keys: &str = match state.get_alert() {
+            Some(_) => "Press [D] to dismiss\nPress [Z] to snooze",
+            None => "Press [Q] to quit\nPress [Enter] key to close",
+        };
         let prompt_text = format!(
-            "{}\n\nPress [Q] to quit\nPress [Enter] key to close",
+            "{}\n\n{}",
             state.prompt.clone().unwrap_or("".to_string()),
+            keys,
         );
         let prompt = Paragraph::new(prompt_text)
             .block(Block::default()
@@ -230,10 +782,20 @@ pub(crate) mod ui {
                 )
                 .split(layout);
         
+            let spinner: &str = match state.get_sync_phase() {
+                Some(_) => SPINNER_FRAMES[(Local::now().timestamp_subsec_millis() / 125) as usize % SPINNER_FRAMES.len()],
+                None => "-",
+            };
+            let profile: String = match state.profile_name() {
+                crate::DEFAULT_PROFILE_NAME => "".to_string(),
+                name => format!("[{}] ", name),
+            };
             let top_left_text: String = format!(
-                ":// {} {} - {}",
+                ":// {} {} {}{} {}",
                 crate_name!(),
                 crate_version!(),
+                profile,
+                spinner,
                 state.modified_string()
             );
             let top_left: Paragraph<'_> = Paragraph::new(top_left_text)
@@ -243,7 +805,8 @@ pub(crate) mod ui {
                 .wrap(Wrap { trim: true });
             
             let top_right_text: &str = state
-            .message
+                .message
+                .as_deref()
                 .unwrap_or("");
             
             let top_right = Paragraph::new(top_right_text)
@@ -257,47 +820,95 @@ pub(crate) mod ui {
             f.render_widget(top_right, top_bar[1]);
     }
 
+    /// Builds the tabs to switch between the saved views
+    fn build_view_tabs<B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &AppState, style: &Style) {
+        let titles: Vec<Spans<'_>> = std::iter::once("All")
+            .chain(state.config().views.iter().map(|v| v.name.as_str()))
+            .map(|t| Spans::from(t.to_string()))
+            .collect();
+        let tabs: Tabs<'_> = Tabs::new(titles)
+            .select(state.get_view().map(|i| i + 1).unwrap_or(0))
+            .style(*style)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+        f.render_widget(tabs, layout);
+    }
+
     /// Build main view of the layout
     fn build_main_view<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
+        let with_tabs: bool = !state.config().views.is_empty();
+        let agenda: bool = state.get_layout() == AppLayout::Agenda;
+        let main_rows = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints(
+                [
+                    Constraint::Length(if with_tabs { 1 } else { 0 }),
+                    Constraint::Min(1),
+                ]
+                .as_ref(),
+            )
+            .split(layout);
+        if with_tabs {
+            build_view_tabs(f, main_rows[0], state, style);
+        }
+
         let main_view = Layout::default()
             .direction(Direction::Horizontal)
             .margin(0)
             .constraints(
                 [
-                    Constraint::Percentage(15),
-                    Constraint::Percentage(55),
-                    Constraint::Percentage(30)
+                    Constraint::Percentage(if agenda { 40 } else { 15 }),
+                    Constraint::Percentage(if agenda { 35 } else { 55 }),
+                    Constraint::Percentage(if agenda { 25 } else { 30 })
                 ]
                 .as_ref(),
             )
-            .split(layout);
+            .split(main_rows[1]);
     
-        let elements: Vec<ListItem<'_>> = state
-            .get_elements()
-            .iter()
-            .map(|e| e.to_list_item())
-            .collect();
+        let elements: Vec<ListItem<'_>> = match agenda {
+            true => build_agenda_items(state),
+            false => state
+                .get_visible_nodes()
+                .iter()
+                .map(|(depth, e)| e.to_list_item(*depth))
+                .collect(),
+        };
 
+        let mut elements_title: String = match state.get_sort().is_manual() {
+            true => "Events".to_string(),
+            false => format!("Events by {}", state.get_sort()),
+        };
+        match state.get_layout() {
+            AppLayout::List => (),
+            AppLayout::Calendar | AppLayout::Week => elements_title.push_str(&state.get_calendar_day().format(" on %a, %d %b").to_string()),
+            AppLayout::Agenda => elements_title = "Agenda".to_string(),
+        }
         let elements_list = List::new(elements)
-            .block(block.clone().title("Events"))
+            .block(block.clone().title(elements_title))
             .style(*style)
             .highlight_style(
                 Style::default()
                 .add_modifier(Modifier::REVERSED)
             );
-        f.render_stateful_widget(
-            elements_list,
-            main_view[0],
-            &mut state.list_state
-        );
+        match agenda {
+            true => f.render_stateful_widget(elements_list, main_view[0], &mut state.agenda_state),
+            false => f.render_stateful_widget(elements_list, main_view[0], &mut state.list_state),
+        }
         
-        let vec_details: Vec<Row> = if let Some(selected_element) = state.get_selected_element() {
-            selected_element
+        let highlights: Vec<&str> = state
+            .get_query()
+            .map(|q| q.highlights())
+            .unwrap_or_default();
+        let vec_details: Vec<Row> = if let Some(selected_node) = state.get_selected_node() {
+            selected_node
                 .get_vecs()
                 .iter()
                 .map(|(k, v)| {
                     Row::new(
-                        vec![k.to_string(), v.to_string()]
+                        vec![
+                            highlight_matches(k, &highlights),
+                            highlight_matches(v, &highlights),
+                        ]
                     )
                     .bottom_margin(1)
                 })
@@ -327,16 +938,16 @@ pub(crate) mod ui {
 
             let content_spans: Vec<Spans<'_>> = content
                 .into_iter()
-                .map(|e| {
+                .flat_map(|e| {
                     let mut key: String = e.0;
                     let value: String = e.1;
 
-                    key.push_str(":");
+                    key.push(':');
 
                     let mut r: Vec<Spans<'_>> = Vec::new();
                     r.push(Spans::from(Span::styled(key,style)));
                     value
-                        .split("\n")
+                        .split('\n')
                         .for_each(|e| {
                             r.push(Spans::from(Span::raw(e.to_string())));
                         });
@@ -344,7 +955,6 @@ pub(crate) mod ui {
 
                     r
                 })
-                .flatten()
                 .collect();
 
             content_spans
@@ -358,6 +968,282 @@ pub(crate) mod ui {
         f.render_widget(details_view, main_view[2]);
     }
 
+    /// Builds the items of the agenda, the nodes are listed under a heading
+    /// for each day they start on. Points the agenda state at the selected node
+    fn build_agenda_items<'a>(state: &mut AppState) -> Vec<ListItem<'a>> {
+        let now: DateTime<Local> = Local::now();
+        let today: NaiveDate = now.date_naive();
+        let selected: Option<usize> = state.list_state.selected();
+        let mut agenda_selected: Option<usize> = None;
+        let mut last_day: Option<NaiveDate> = None;
+        let mut items: Vec<ListItem<'a>> = Vec::new();
+
+        let rows: Vec<(Vec<usize>, DateTime<Local>, DateTime<Local>)> = state.agenda_rows();
+        for (i, ((_, node), (_, start, end))) in state.get_visible_nodes().iter().zip(rows.iter()).enumerate() {
+            let RegistryNode::Element(e) = node else {
+                continue;
+            };
+            let (start, end): (DateTime<Local>, DateTime<Local>) = (*start, *end);
+            if last_day != Some(start.date_naive()) {
+                last_day = Some(start.date_naive());
+                items.push(ListItem::new(data_helpers::day_heading(start.date_naive(), today))
+                    .style(Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED)));
+            }
+            if selected == Some(i) {
+                agenda_selected = Some(items.len());
+            }
+
+            let time: String = match (end > start, end.date_naive() == start.date_naive()) {
+                (false, _) => start.format("%H:%M").to_string(),
+                (true, true) => format!("{}-{}", start.format("%H:%M"), end.format("%H:%M")),
+                (true, false) => format!("{}-{}", start.format("%H:%M"), end.format("%d %b %H:%M")),
+            };
+            let mut text: String = format!(
+                "{}{} {}{}",
+                crate::DISPLAY_INDENT_STRING,
+                time,
+                if e.is_done() { "[x] " } else { "" },
+                e.title().unwrap_or("<no title>".to_string())
+            );
+            if let Some(location) = e.location() {
+                text.push_str(&format!(" @ {}", location));
+            }
+            let mut style: Style = match end < now && !e.is_done() {
+                true => Style::default().fg(Color::Rgb(0xFF, 0x2A, 0x6D)).add_modifier(Modifier::BOLD),
+                false => entry_style(e),
+            };
+            if node.removed() {
+                style = style.add_modifier(Modifier::CROSSED_OUT);
+            }
+            items.push(ListItem::new(text).style(style));
+        }
+
+        state.agenda_state.select(agenda_selected);
+        items
+    }
+
+    /// Builds the month grid of the calendar with the events of the selected
+    /// day listed below it
+    fn build_calendar_view<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
+        let calendar_rows: Vec<Rect> = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints(
+                [
+                    Constraint::Percentage(60),
+                    Constraint::Min(1),
+                ]
+                .as_ref(),
+            )
+            .split(layout);
+
+        let selected: NaiveDate = state.get_calendar_day();
+        let today: NaiveDate = Local::now().date_naive();
+        let first: NaiveDate = selected.with_day(1).unwrap_or(selected);
+        let days_in_month: u32 = first
+            .checked_add_months(Months::new(1))
+            .map(|next| (next - first).num_days() as u32)
+            .unwrap_or(31);
+        let offset: u32 = first.weekday().num_days_from_monday();
+        let weeks: u32 = (offset + days_in_month).div_ceil(7);
+        let row_height: u16 = (calendar_rows[0].height.saturating_sub(3) / weeks as u16).max(1);
+
+        let rows: Vec<Row<'_>> = (0..weeks)
+            .map(|week| {
+                let cells: Vec<Cell<'_>> = (0..7)
+                    .map(|weekday| {
+                        let day: NaiveDate = first + chrono::Duration::days((week * 7 + weekday) as i64 - offset as i64);
+                        let mut day_style: Style = match day.month() == first.month() {
+                            true => Style::default(),
+                            false => Style::default().add_modifier(Modifier::DIM),
+                        };
+                        if day == today {
+                            day_style = day_style.add_modifier(Modifier::BOLD);
+                        }
+                        if day == selected {
+                            day_style = day_style.add_modifier(Modifier::REVERSED);
+                        }
+
+                        let entries: Vec<&EntryNode> = state.entries_on_day(day);
+                        let space: usize = row_height.saturating_sub(1) as usize;
+                        let shown: usize = match entries.len() > space {
+                            true => space.saturating_sub(1),
+                            false => entries.len(),
+                        };
+                        let mut lines: Vec<Spans<'_>> = vec![Spans::from(Span::styled(format!("{:>2}", day.day()), day_style))];
+                        entries
+                            .iter()
+                            .take(shown)
+                            .for_each(|e| {
+                                let title: String = e.title().unwrap_or_default();
+                                let line: String = match e.occurrence_on_day(day) {
+                                    Some((start, _)) if start.date_naive() == day => format!("{} {}", start.format("%H:%M"), title),
+                                    _ => format!("… {}", title),
+                                };
+                                lines.push(Spans::from(Span::styled(line, entry_style(e))));
+                            });
+                        if shown < entries.len() {
+                            lines.push(Spans::from(Span::styled(
+                                format!("+{} more", entries.len() - shown),
+                                Style::default().add_modifier(Modifier::ITALIC)
+                            )));
+                        }
+                        Cell::from(Text::from(lines))
+                    })
+                    .collect();
+                Row::new(cells).height(row_height)
+            })
+            .collect();
+
+        let header: Row<'_> = Row::new(vec!["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"])
+            .style(Style::default().add_modifier(Modifier::BOLD));
+        let widths: [Constraint; 7] = [Constraint::Ratio(1, 7); 7];
+        let border_style: Style = match state.focused_on {
+            AppFocus::Calendar => Style::default().fg(Color::Rgb(0xFF, 0x2A, 0x6D)),
+            _ => Style::default().fg(Color::Rgb(0x05, 0xD9, 0xE8)),
+        };
+        let month_table: Table<'_> = Table::new(rows)
+            .header(header)
+            .block(block.clone().title(selected.format("%B %Y").to_string()).border_style(border_style))
+            .widths(&widths)
+            .column_spacing(1)
+            .style(*style);
+
+        f.render_widget(month_table, calendar_rows[0]);
+
+        build_main_view(f, calendar_rows[1], state, block, style);
+    }
+
+    /// Builds the hourly grid of the week with the events of the selected day
+    /// listed below it. Events overlapping with others are flagged with a '!'
+    fn build_week_view<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
+        let week_rows: Vec<Rect> = Layout::default()
+            .direction(Direction::Vertical)
+            .margin(0)
+            .constraints(
+                [
+                    Constraint::Percentage(60),
+                    Constraint::Min(1),
+                ]
+                .as_ref(),
+            )
+            .split(layout);
+
+        let selected: NaiveDate = state.get_calendar_day();
+        let selected_hour: u32 = state.get_calendar_hour();
+        let today: NaiveDate = Local::now().date_naive();
+        let monday: NaiveDate = selected - chrono::Duration::days(selected.weekday().num_days_from_monday() as i64);
+        let days: Vec<NaiveDate> = (0..7).map(|i| monday + chrono::Duration::days(i)).collect();
+
+        // Keep the selected hour visible, showing some hours before it
+        let hours_shown: u32 = (week_rows[0].height.saturating_sub(3) as u32).clamp(1, 24);
+        let first_hour: u32 = selected_hour
+            .saturating_sub(hours_shown / 3)
+            .min(24 - hours_shown);
+
+        let mut conflicts: usize = 0;
+        let columns: Vec<Vec<Cell<'_>>> = days
+            .iter()
+            .map(|day| {
+                let entries: Vec<&EntryNode> = state.entries_on_day(*day);
+                let overlapping: Vec<bool> = EntryNode::overlapping(&entries, *day);
+                conflicts += overlapping.iter().filter(|o| **o).count();
+
+                (first_hour..first_hour + hours_shown)
+                    .map(|hour| {
+                        let slot: Option<(DateTime<Local>, DateTime<Local>)> = day
+                            .and_hms_opt(hour, 0, 0)
+                            .and_then(|t| t.and_local_timezone(Local).earliest())
+                            .map(|t| (t, t + chrono::Duration::hours(1)));
+                        let in_slot: Vec<(&EntryNode, bool)> = match slot {
+                            Some((from, to)) => entries
+                                .iter()
+                                .zip(overlapping.iter())
+                                .filter(|(e, _)| e.occupies(from, to))
+                                .map(|(e, o)| (*e, *o))
+                                .collect(),
+                            None => Vec::new(),
+                        };
+
+                        let mut cell_style: Style = match in_slot.first() {
+                            Some((_, true)) => Style::default().fg(Color::Rgb(0xFF, 0x2A, 0x6D)).add_modifier(Modifier::BOLD),
+                            Some((e, false)) => entry_style(e),
+                            None => Style::default(),
+                        };
+                        if *day == selected && hour == selected_hour {
+                            cell_style = cell_style.add_modifier(Modifier::REVERSED);
+                        }
+
+                        let text: String = match in_slot.first() {
+                            Some((e, overlaps)) => {
+                                let starts_here: bool = hour == first_hour || slot.is_some_and(|(from, to)| {
+                                    e.occurrences(from, to).first().is_some_and(|(start, _)| *start >= from)
+                                });
+                                let marker: &str = if *overlaps { "!" } else { "" };
+                                let more: String = match in_slot.len() {
+                                    1 => "".to_string(),
+                                    n => format!(" +{}", n - 1),
+                                };
+                                match starts_here {
+                                    true => format!("{}{}{}", marker, e.title().unwrap_or("<no title>".to_string()), more),
+                                    false => format!("{}│{}", marker, more),
+                                }
+                            },
+                            None => "".to_string(),
+                        };
+                        Cell::from(text).style(cell_style)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let rows: Vec<Row<'_>> = (0..hours_shown as usize)
+            .map(|i| {
+                let mut cells: Vec<Cell<'_>> = vec![Cell::from(format!("{:02}:00", first_hour as usize + i))
+                    .style(Style::default().add_modifier(Modifier::DIM))];
+                cells.extend(columns.iter().map(|c| c[i].clone()));
+                Row::new(cells)
+            })
+            .collect();
+
+        let header: Row<'_> = Row::new(
+            std::iter::once(Cell::from(""))
+                .chain(days.iter().map(|day| {
+                    let day_style: Style = match *day == today {
+                        true => Style::default().add_modifier(Modifier::BOLD | Modifier::UNDERLINED),
+                        false => Style::default().add_modifier(Modifier::BOLD),
+                    };
+                    Cell::from(day.format("%a %d").to_string()).style(day_style)
+                }))
+                .collect::<Vec<Cell<'_>>>()
+        );
+        let mut widths: Vec<Constraint> = vec![Constraint::Length(5)];
+        widths.extend(std::iter::repeat_n(Constraint::Ratio(1, 7), 7));
+        let mut title: String = format!(
+            "Week {} ({} - {})",
+            monday.iso_week().week(),
+            monday.format("%d %b"),
+            days[6].format("%d %b %Y")
+        );
+        if conflicts > 0 {
+            title.push_str(&format!(" ! {} overlapping", conflicts));
+        }
+        let border_style: Style = match state.focused_on {
+            AppFocus::Calendar => Style::default().fg(Color::Rgb(0xFF, 0x2A, 0x6D)),
+            _ => Style::default().fg(Color::Rgb(0x05, 0xD9, 0xE8)),
+        };
+        let week_table: Table<'_> = Table::new(rows)
+            .header(header)
+            .block(block.clone().title(title).border_style(border_style))
+            .widths(&widths)
+            .column_spacing(1)
+            .style(*style);
+
+        f.render_widget(week_table, week_rows[0]);
+
+        build_main_view(f, week_rows[1], state, block, style);
+    }
+
     /// Builds the bottom bar of the layout
     fn build_bottom_bar<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
         let bottom_content: Spans<'_> = {
@@ -373,11 +1259,23 @@ pub(crate) mod ui {
                         AppFocus::Edit => {
                             "INS "
                         },
+                        AppFocus::Conflicts => {
+                            "MRG "
+                        },
+                        AppFocus::Search => {
+                            "SRC /"
+                        },
+                        AppFocus::Calendar => {
+                            "CAL "
+                        },
                     },
                     Style::default().add_modifier(Modifier::BOLD)
                 ),
-                Span::raw(state.get_edit().unwrap_or("".to_string())),
-                if state.is_editing() {
+                match state.focused_on {
+                    AppFocus::Search => Span::raw(state.get_search().unwrap_or("").to_string()),
+                    _ => Span::raw(state.get_edit().unwrap_or("".to_string())),
+                },
+                if state.is_editing() || state.focused_on == AppFocus::Search {
                     Span::styled(
                         "_",
                         Style::default().add_modifier(Modifier::SLOW_BLINK)
@@ -396,9 +1294,85 @@ pub(crate) mod ui {
         f.render_widget(bottom_editor, layout);
     }
 
+    /// Build the view listing all conflicts left from merging remote changes
+    fn build_conflict_view<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
+        let display_value = |v: &Option<String>| -> String {
+            v.clone().unwrap_or("<none>".to_string())
+        };
+
+        let rows: Vec<Row> = state
+            .get_conflicts()
+            .iter()
+            .map(|c| {
+                Row::new(
+                    vec![
+                        c.title.to_string(),
+                        c.attribute.to_string(),
+                        display_value(&c.local),
+                        display_value(&c.remote),
+                        display_value(&c.base),
+                    ]
+                )
+                .bottom_margin(1)
+            })
+            .collect();
+
+        let conflicts_table = Table::new(rows)
+            .header(
+                Row::new(vec!["Entry", "Attribute", "Local", "Remote", "Base"])
+                    .style(Style::default().add_modifier(Modifier::BOLD))
+                    .bottom_margin(1)
+            )
+            .block(block.title("Conflicts"))
+            .widths(&[
+                Constraint::Percentage(20),
+                Constraint::Percentage(14),
+                Constraint::Percentage(22),
+                Constraint::Percentage(22),
+                Constraint::Percentage(22),
+            ])
+            .column_spacing(1)
+            .style(*style)
+            .highlight_style(Style::default().add_modifier(Modifier::REVERSED));
+
+        f.render_stateful_widget(
+            conflicts_table,
+            layout,
+            &mut state.conflict_state
+        );
+    }
+
+    /// Builds the bar showing the query the events are filtered by
+    fn build_filter_bar<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &AppState, block: Block<'a>, style: &Style) {
+        let mut content: Vec<Span<'_>> = vec![
+            Span::styled("Filter: ", Style::default().add_modifier(Modifier::BOLD)),
+            Span::raw(state.get_query().map(|q| q.to_string()).unwrap_or_default()),
+        ];
+        if let Some(e) = state.get_query_error() {
+            content.push(Span::styled(
+                format!("  ({})", e),
+                Style::default().add_modifier(Modifier::ITALIC)
+            ));
+        }
+
+        let filter_bar: Paragraph<'_> = Paragraph::new(Spans::from(content))
+            .block(block)
+            .style(*style)
+            .wrap(Wrap { trim: true });
+        f.render_widget(filter_bar, layout);
+    }
+
     /// Builds the footer of the layout
-    fn build_footer<'a, B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &mut AppState, block: Block<'a>, style: &Style) {
-        let actions_text: String = AppCommand::get_command_list_string().join(" | ");
+    fn build_footer<B: tui::backend::Backend>(f: &mut Frame<B>, layout: Rect, state: &AppState, block: Block<'_>, style: &Style) {
+        let actions_text: String = match state.focused_on {
+            AppFocus::Conflicts => "[a] keep local | [d] take remote | [b] restore base | [Esc] back".to_string(),
+            AppFocus::Calendar => match state.get_layout() {
+                AppLayout::Week => "[wasd] move slot | [A/D] move week | [Enter] events of the day | [f]ill slot with new | [l]ayout".to_string(),
+                _ => "[wasd] move day | [A/D] move month | [Enter] events of the day | [f]ill with new | [l]ayout".to_string(),
+            },
+            AppFocus::Search => "[Enter] keep filter | [Esc] clear filter | text name:text due<tomorrow has:alert -excluded".to_string(),
+            _ => AppCommand::get_command_list_string().join(" | "),
+        };
         let actions: Paragraph<'_> = Paragraph::new(actions_text)
             .block(block)
             .style(*style)
@@ -426,12 +1400,16 @@ pub(crate) mod ui {
             .fg(Color::Rgb(0xFF, 0x2A, 0x6D))
             .bg(Color::Rgb(0x01, 0x01, 0x2B));
     
+        let filtering: bool = state.focused_on != AppFocus::Conflicts
+            && (state.get_query().is_some() || state.get_query_error().is_some());
+
         let main_layout = Layout::default()
             .margin(0)
             .direction(Direction::Vertical)
             .constraints(
                 [
                     Constraint::Length(1),
+                    Constraint::Length(if filtering { 1 } else { 0 }),
                     Constraint::Min(1),
                     Constraint::Length(1),
                     Constraint::Length(1),
@@ -442,14 +1420,27 @@ pub(crate) mod ui {
     
         // Top Bar
         build_top_bar(f, main_layout[0], state, alt_block.clone(), &alt_style);
+
+        // Filter Bar
+        if filtering {
+            build_filter_bar(f, main_layout[1], state, alt_block.clone(), &standard_style);
+        }
     
         // Main View
-        build_main_view(f, main_layout[1], state, standard_block, &standard_style);
+        match state.focused_on {
+            AppFocus::Conflicts => build_conflict_view(f, main_layout[2], state, standard_block, &standard_style),
+            _ => match state.get_layout() {
+                AppLayout::List => build_main_view(f, main_layout[2], state, standard_block, &standard_style),
+                AppLayout::Calendar => build_calendar_view(f, main_layout[2], state, standard_block, &standard_style),
+                AppLayout::Agenda => build_main_view(f, main_layout[2], state, standard_block, &standard_style),
+                AppLayout::Week => build_week_view(f, main_layout[2], state, standard_block, &standard_style),
+            },
+        }
     
         // Bottom Text Lane
-        build_bottom_bar(f, main_layout[2], state, alt_block.clone(), &standard_style);
+        build_bottom_bar(f, main_layout[3], state, alt_block.clone(), &standard_style);
 
         // Footer
-        build_footer(f, main_layout[3], state, alt_block, &alt_style);
+        build_footer(f, main_layout[4], state, alt_block, &alt_style);
     }
 }
\ No newline at end of file
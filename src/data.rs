@@ -1,20 +1,27 @@
 pub(crate) mod data_helpers {
     use crate::data::data_types::{NodeValue, NodeName};
-    use chrono::{Duration, Local, Days, Months, DateTime, NaiveDateTime, LocalResult, NaiveTime};
+    use chrono::{Duration, Local, Days, Months, DateTime, NaiveDate, NaiveDateTime, LocalResult, NaiveTime};
     use quick_xml::{Reader, events::{Event, BytesStart}};
+    use rand::Rng;
     use std::collections::HashMap;
+    use std::fs::{self, File, Metadata, OpenOptions};
+    use std::io::Write;
+    use std::process::{Command, ExitStatus, Output, Stdio};
     use std::str;
 
     /// Gets the value of the id attribute of any node
-    pub fn get_id_attribute<'a>(reader: &Reader<&[u8]>, element: &BytesStart<'a>) -> Option<u16> {
+    pub fn get_id_attribute(reader: &Reader<&[u8]>, element: &BytesStart<'_>) -> Option<u16> {
+        get_number_attribute(reader, element, b"id")
+    }
+
+    /// Gets the value of the attribute of the given name of any node as a number
+    pub fn get_number_attribute(reader: &Reader<&[u8]>, element: &BytesStart<'_>, name: &[u8]) -> Option<u16> {
         element
             .attributes()
-            .into_iter()
             .filter_map(|f| f.ok())
-            .filter(|e| e.key.local_name().as_ref() == b"id")
+            .filter(|e| e.key.local_name().as_ref() == name)
             .map(|v| {
-                if let Ok(mut attr) = v.decode_and_unescape_value(&reader) {
-                    attr = attr.into();
+                if let Ok(attr) = v.decode_and_unescape_value(reader) {
                     attr.parse::<u16>().ok()
                 } else {
                     None
@@ -24,9 +31,34 @@ pub(crate) mod data_helpers {
             .flatten()
     }
 
+    /// Returns whether the given element is an entry or a directory
+    pub fn is_registry_node(element: &BytesStart<'_>) -> bool {
+        matches!(element.name().as_ref(), b"entry" | b"directory")
+    }
+
+    /// Returns whether the attribute of the given name is set to true on any node
+    pub fn get_flag_attribute(reader: &Reader<&[u8]>, element: &BytesStart<'_>, name: &[u8]) -> bool {
+        element
+            .attributes()
+            .filter_map(|f| f.ok())
+            .filter(|e| e.key.local_name().as_ref() == name)
+            .any(|v| v.decode_and_unescape_value(reader).is_ok_and(|attr| attr == "true"))
+    }
+
+    /// Generates a new random ID that is not in existing ids and adds it to them
+    pub fn generate_id(existing_ids: &mut Vec<u16>) -> u16 {
+        let mut rng = rand::thread_rng();
+        let mut new_id: u16 = 0;
+        while new_id == 0 || existing_ids.contains(&new_id) {
+            new_id = rng.gen::<u16>();
+        }
+        existing_ids.push(new_id);
+        new_id
+    }
+
     /// Reads with the given xml reader the content into an NodeName and NodeValue
     //pub fn read_node<'t>(mut reader: Reader<&'t [u8]>, parent: &[u8]) -> Result<(Reader<&'t [u8]>, NodeValue), quick_xml::Error> {
-    pub fn read_node<'t>(reader: &mut Reader<&'t [u8]>, parent: &[u8]) -> Result<NodeValue, quick_xml::Error> {
+    pub fn read_node(reader: &mut Reader<&[u8]>, parent: &[u8]) -> Result<NodeValue, quick_xml::Error> {
         let mut buf: Vec<u8> = Vec::new();
 
         let mut result: NodeValue = NodeValue::Text("".to_string());
@@ -36,8 +68,7 @@ pub(crate) mod data_helpers {
         loop {
             match reader.read_event_into(&mut buf)? {
                 Event::Start(e) => {
-                    let found: NodeValue;
-                    found = read_node(reader, e.name().as_ref())?;
+                    let found: NodeValue = read_node(reader, e.name().as_ref())?;
                     read_values.insert(
                         NodeName::from_str(str::from_utf8(e.name().as_ref()).unwrap_or("")),
                         found
@@ -45,13 +76,20 @@ pub(crate) mod data_helpers {
                 }
                 Event::Text(e) => {
                     result = NodeValue::Text(
-                        str::from_utf8(
-                            e.into_inner().as_ref()
-                        )
-                        .unwrap_or("")
-                        .to_string()
+                        e.unescape()?.to_string()
+                    )
+                },
+                Event::CData(e) => {
+                    result = NodeValue::Text(
+                        String::from_utf8_lossy(&e.into_inner()).to_string()
                     )
                 },
+                Event::Empty(e) => {
+                    read_values.insert(
+                        NodeName::from_str(str::from_utf8(e.name().as_ref()).unwrap_or("")),
+                        NodeValue::Text("".to_string())
+                    );
+                },
                 Event::End(e) if e.name().as_ref() == parent => {
                     break
                 },
@@ -67,6 +105,345 @@ pub(crate) mod data_helpers {
         }
     }
 
+    /// Returns the nested tree as an Vec over each nested level
+    fn flatten_tree<'a>(name_stack: &mut Vec<&'a NodeName>, nodes: &[(&'a NodeName, &'a NodeValue)]) -> Vec<(String, String)> {
+        let mut r: Vec<(String, String)> = Vec::new();
+        nodes
+            .iter()
+            .for_each(|(node_name, node_value)| {
+                name_stack.push(node_name);
+                match node_value {
+                    NodeValue::Text(t) => {
+                        r.push(
+                            (
+                                name_stack
+                                    .iter()
+                                    .map(|e| e.to_string())
+                                    .collect::<Vec<String>>()
+                                    .join(crate::DISPLAY_NESTING_STRING),
+                                t.to_string()
+                            )
+                        );
+                    },
+                    NodeValue::NestedNode(n) => {
+                        r.append(&mut flatten_tree(name_stack, &n.iter().collect::<Vec<_>>()))
+                    }
+                }
+                name_stack.pop();
+            });
+
+        r
+    }
+
+    /// Flattens the given nodes into pairs of their nesting path and value,
+    /// sorted by the order of the node names
+    pub fn flatten_sorted(nodes: &HashMap<NodeName, NodeValue>) -> Vec<(String, String)> {
+        let mut nodes_sorted: Vec<(&NodeName, &NodeValue)> = nodes
+            .iter()
+            .collect();
+
+        nodes_sorted.sort_by_key(|a| a.0.order());
+
+        flatten_tree(&mut Vec::new(), &nodes_sorted)
+    }
+
+    /// Splits a flattened attribute name into the chain of nested node names
+    pub fn name_chain(name: &str) -> Vec<NodeName> {
+        name
+            .split(crate::DISPLAY_NESTING_STRING)
+            .map(NodeName::from_str)
+            .collect()
+    }
+
+    /// Inserts the value into the map following the chain of nested node names,
+    /// creating nested nodes where needed. Returns the replaced value
+    pub fn insert_chain_to_map(map: &mut HashMap<NodeName, NodeValue>, mut name_chain: Vec<NodeName>, value: NodeValue) -> Option<NodeValue> {
+        let mut r = None;
+        // TODO: Use VecDeque instead here?
+        let next_name: NodeName = name_chain.remove(0);
+        if name_chain.is_empty() {
+            r = map.insert(next_name, value);
+        } else {
+            match map.get(&next_name) {
+                Some(NodeValue::NestedNode(_)) => {},
+                _ => {
+                    let blank_map: HashMap<NodeName, NodeValue> = HashMap::new();
+                    map.insert(next_name.clone(), NodeValue::NestedNode(blank_map));
+                }
+            }
+            if let Some(NodeValue::NestedNode(n)) = map.get_mut(&next_name) {
+                r = insert_chain_to_map(n, name_chain, value);
+            }
+        }
+
+        r
+    }
+
+    /// Removes the value found by following the chain of nested node names,
+    /// nested nodes left empty are removed as well. Returns the removed value
+    pub fn remove_chain_from_map(map: &mut HashMap<NodeName, NodeValue>, mut name_chain: Vec<NodeName>) -> Option<NodeValue> {
+        let next_name: NodeName = name_chain.remove(0);
+        if name_chain.is_empty() {
+            return map.remove(&next_name);
+        }
+        let Some(NodeValue::NestedNode(n)) = map.get_mut(&next_name) else {
+            return None;
+        };
+        let r = remove_chain_from_map(n, name_chain);
+        if n.is_empty() {
+            map.remove(&next_name);
+        }
+        r
+    }
+
+    /// Returns the value found by following the chain of nested node names
+    pub fn get_chain_from_map<'a>(map: &'a HashMap<NodeName, NodeValue>, name_chain: &[NodeName]) -> Option<&'a NodeValue> {
+        let (next_name, rest) = name_chain.split_first()?;
+        let value: &NodeValue = map.get(next_name)?;
+        match (rest.is_empty(), value) {
+            (true, v) => Some(v),
+            (false, NodeValue::NestedNode(n)) => get_chain_from_map(n, rest),
+            (false, NodeValue::Text(_)) => None,
+        }
+    }
+
+    /// Returns the timestamp of the local date and time
+    pub fn local_timestamp(datetime: NaiveDateTime) -> Option<i64> {
+        match datetime.and_local_timezone(Local) {
+            LocalResult::Single(d) | LocalResult::Ambiguous(d, _) => Some(d.timestamp()),
+            LocalResult::None => None,
+        }
+    }
+
+    /// Returns the start of the day and of the following day in local time
+    pub fn local_day(day: NaiveDate) -> Option<(DateTime<Local>, DateTime<Local>)> {
+        let start: DateTime<Local> = day.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest()?;
+        let end: DateTime<Local> = day.succ_opt()?.and_time(NaiveTime::MIN).and_local_timezone(Local).earliest()?;
+        Some((start, end))
+    }
+
+    /// Compares two attribute values numerically if both are numbers and
+    /// alphabetically ignoring the case otherwise
+    pub fn compare_values(a: &str, b: &str) -> std::cmp::Ordering {
+        match (a.trim().parse::<f64>(), b.trim().parse::<f64>()) {
+            (Ok(x), Ok(y)) => x.partial_cmp(&y).unwrap_or(std::cmp::Ordering::Equal),
+            _ => a.to_lowercase().cmp(&b.to_lowercase()),
+        }
+    }
+
+    /// Splits a query into its terms at whitespace, keeping whitespace
+    /// inside of double quotes and removing the quotes
+    pub fn split_query(query: &str) -> Result<Vec<String>, String> {
+        let mut terms: Vec<String> = Vec::new();
+        let mut term: String = String::new();
+        let mut quoted: bool = false;
+        query.chars().for_each(|c| match c {
+            '"' => quoted = !quoted,
+            c if c.is_whitespace() && !quoted => {
+                if !term.is_empty() {
+                    terms.push(std::mem::take(&mut term));
+                }
+            },
+            c => term.push(c),
+        });
+        if quoted {
+            return Err("Missing closing quote".to_string());
+        }
+        if !term.is_empty() {
+            terms.push(term);
+        }
+        Ok(terms)
+    }
+
+    /// Parses a timestamp, 'now' or any time a cmdlet understands into a
+    /// timestamp
+    pub fn parse_time(input: &str) -> Option<i64> {
+        if let Ok(timestamp) = input.parse::<i64>() {
+            return Some(timestamp);
+        }
+        match input.to_lowercase().as_str() {
+            "now" => Some(Local::now().timestamp()),
+            i => parse_cmdlet(format!("{}{}", crate::INPUT_CMDLET_PREFIX, i))
+                .ok()?
+                .parse::<i64>()
+                .ok(),
+        }
+    }
+
+    /// Merges the attributes of a node that were changed locally and remotely
+    /// since the base was taken. Every flattened attribute is merged on its
+    /// own, an attribute changed on both sides to different values is a
+    /// conflict and keeps its local value.
+    /// Returns the merged attributes and the names of all conflicting ones
+    pub fn merge_attributes(base: &HashMap<NodeName, NodeValue>, local: &HashMap<NodeName, NodeValue>, remote: &HashMap<NodeName, NodeValue>) -> (HashMap<NodeName, NodeValue>, Vec<String>) {
+        let base: HashMap<String, String> = flatten_sorted(base).into_iter().collect();
+        let local: HashMap<String, String> = flatten_sorted(local).into_iter().collect();
+        let remote: HashMap<String, String> = flatten_sorted(remote).into_iter().collect();
+
+        let mut names: Vec<&String> = base.keys().chain(local.keys()).chain(remote.keys()).collect();
+        names.sort();
+        names.dedup();
+
+        let mut merged: HashMap<NodeName, NodeValue> = HashMap::new();
+        let mut conflicts: Vec<String> = Vec::new();
+
+        names
+            .into_iter()
+            .for_each(|name| {
+                let (b, l, r) = (base.get(name), local.get(name), remote.get(name));
+                let value: Option<&String> = if l == r || r == b {
+                    l
+                } else if l == b {
+                    r
+                } else {
+                    conflicts.push(name.to_string());
+                    l
+                };
+                if let Some(v) = value {
+                    insert_chain_to_map(&mut merged, name_chain(name), NodeValue::Text(v.to_string()));
+                }
+            });
+
+        (merged, conflicts)
+    }
+
+    /// Returns the heading a day is listed under in the agenda, days of the
+    /// coming week are named by their weekday
+    pub fn day_heading(day: NaiveDate, today: NaiveDate) -> String {
+        match (day - today).num_days() {
+            -1 => "Yesterday".to_string(),
+            0 => "Today".to_string(),
+            1 => "Tomorrow".to_string(),
+            2..=6 => day.format("%A").to_string(),
+            _ => day.format("%a, %d %b %Y").to_string(),
+        }
+    }
+
+    /// Returns the byte ranges of all occurences of search in text, ignoring
+    /// the case
+    pub fn find_matches(text: &str, search: &str) -> Vec<(usize, usize)> {
+        let search: String = search.to_lowercase();
+        if search.is_empty() {
+            return Vec::new();
+        }
+
+        // Lowercasing may change the length, so remember where each lowercase
+        // char starts in the original text
+        let mut lower: String = String::new();
+        let mut origin: Vec<usize> = Vec::new();
+        text.char_indices().for_each(|(i, c)| {
+            c.to_lowercase().for_each(|l| {
+                origin.extend(std::iter::repeat_n(i, l.len_utf8()));
+                lower.push(l);
+            });
+        });
+        origin.push(text.len());
+
+        let mut ranges: Vec<(usize, usize)> = Vec::new();
+        let mut from: usize = 0;
+        while let Some(pos) = lower[from..].find(&search) {
+            let start: usize = from + pos;
+            let end: usize = start + search.len();
+            // Extend the end to the start of the next original char
+            let end_origin: usize = match origin[end..].iter().find(|o| **o > origin[end - 1]) {
+                Some(o) => *o,
+                None => text.len(),
+            };
+            ranges.push((origin[start], end_origin));
+            from = end;
+        }
+        ranges
+    }
+
+    /// Returns a command running the given command line in a shell
+    fn shell(command: &str) -> Command {
+        #[cfg(windows)]
+        let mut shell: Command = Command::new("cmd");
+        #[cfg(windows)]
+        shell.arg("/C");
+        #[cfg(not(windows))]
+        let mut shell: Command = Command::new("sh");
+        #[cfg(not(windows))]
+        shell.arg("-c");
+
+        shell.arg(command);
+        shell
+    }
+
+    /// Runs the command in a shell and returns the first line it prints
+    pub fn run_secret_command(command: &str) -> Result<String, String> {
+        let output: Output = shell(command)
+            .stdin(Stdio::null())
+            .output()
+            .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+        if !output.status.success() {
+            return Err(format!("'{}' exited with {}", command, output.status));
+        }
+        match String::from_utf8_lossy(&output.stdout).lines().next() {
+            Some(line) if !line.is_empty() => Ok(line.to_string()),
+            _ => Err(format!("'{}' did not print a secret", command)),
+        }
+    }
+
+    /// Runs the hook command in a shell with the arguments and environment
+    /// variables, the output of the hook is passed through. In POSIX shells
+    /// the arguments are available as $1, $2, ...
+    pub fn run_hook(command: &str, args: &[&str], envs: &[(&str, &str)]) -> Result<(), String> {
+        let mut hook: Command = shell(command);
+        #[cfg(not(windows))]
+        hook.arg("freemind");
+        let status: ExitStatus = hook
+            .args(args)
+            .envs(envs.iter().copied())
+            .stdin(Stdio::null())
+            .status()
+            .map_err(|e| format!("Failed to run '{}': {}", command, e))?;
+        match status.success() {
+            true => Ok(()),
+            false => Err(format!("'{}' exited with {}", command, status)),
+        }
+    }
+
+    /// Reads the secret from the file, which must not be accessible by
+    /// anyone but its owner
+    pub fn read_secret_file(path: &str) -> Result<String, String> {
+        let metadata: Metadata = fs::metadata(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode: u32 = metadata.permissions().mode();
+            if mode & 0o077 != 0 {
+                return Err(format!("'{}' is accessible by other users (mode {:o}), restrict it with `chmod 600`", path, mode & 0o777));
+            }
+        }
+        if !metadata.is_file() {
+            return Err(format!("'{}' is not a file", path));
+        }
+        let content: String = fs::read_to_string(path)
+            .map_err(|e| format!("Failed to read '{}': {}", path, e))?;
+        match content.trim_end_matches(['\r', '\n']) {
+            "" => Err(format!("'{}' is empty", path)),
+            secret => Ok(secret.to_string()),
+        }
+    }
+
+    /// Writes the secret to a new file that only its owner can access, an
+    /// existing file is never overwritten
+    pub fn write_secret_file(path: &str, secret: &str) -> Result<(), String> {
+        let mut options: OpenOptions = OpenOptions::new();
+        options.write(true).create_new(true);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::OpenOptionsExt;
+            options.mode(0o600);
+        }
+        let mut file: File = options
+            .open(path)
+            .map_err(|e| format!("Failed to create '{}': {}", path, e))?;
+        writeln!(file, "{}", secret).map_err(|e| format!("Failed to write '{}': {}", path, e))
+    }
+
     /*
     fn local_time_into_timestamp<T: chrono::TimeZone>(time: chrono::DateTime<T>) -> Result<i64, ()> {
         Ok(0)
@@ -91,9 +468,9 @@ pub(crate) mod data_helpers {
                     _ => None
                 };
 
-                return due_date;
+                due_date
             } else {
-                return None;
+                None
             }
     }
 
@@ -124,12 +501,12 @@ pub(crate) mod data_helpers {
                     time = Some(t);
                 }
             });
-        return time;
+        time
     }
 
     fn match_loose_time_at_to_due(mut input: &str) -> Result<i64, ()> {
-        if input.starts_with("at") {
-            input = input[2..].trim();
+        if let Some(stripped) = input.strip_prefix("at") {
+            input = stripped.trim();
         }
 
         let due: chrono::DateTime<Local> = Local::now();
@@ -196,10 +573,10 @@ pub(crate) mod data_helpers {
         let mut and_time: bool = false;
       
         match inputs.next() {
-            Some(inp) if inp == "tomorrow" => {
+            Some("tomorrow") => {
                 due = due.checked_add_days(Days::new(1)).unwrap_or(due);
             },
-            Some(inp) if inp == "at" => {
+            Some("at") => {
                 and_time = true;
             },
             Some(_) => {
@@ -258,8 +635,8 @@ pub(crate) mod data_helpers {
     /// simply returns the input String if no cmdlet was used
     /// Returns Result::Err if a cmdlet was used but parsing failed
     pub fn parse_cmdlet(input: String) -> Result<String, ()> {
-        if input.starts_with(crate::INPUT_CMDLET_PREFIX) {
-            let inp: &str = &input[crate::INPUT_CMDLET_PREFIX.len()..].to_lowercase();
+        if let Some(stripped) = input.strip_prefix(crate::INPUT_CMDLET_PREFIX) {
+            let inp: &str = &stripped.to_lowercase();
             // Match Times losely
             let parsed: String = if inp.starts_with("in")  {
                 match_loose_time_in_to_due(inp)?.to_string()
@@ -271,25 +648,39 @@ pub(crate) mod data_helpers {
                 match_loose_time_at_to_due(inp)?.to_string()
             };
 
-            return Ok(parsed);
+            Ok(parsed)
         } else {
-            return Ok(input);
+            Ok(input)
         }
     }
 }
 
 pub(crate) mod data_types {
     use crate::data::data_helpers;
+    use chrono::{DateTime, Datelike, Days, Local, LocalResult, Months, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
     use crossterm::event::KeyCode;
     use quick_xml::{Reader, events::{attributes::Attribute, Event, BytesStart, BytesText, BytesEnd}, Writer};
-    use rand::Rng;
     use reqwest::{Client, Response, header::HeaderValue};
     use serde::{Serialize, Deserialize};
-    use std::{fmt, io::Cursor, str, collections::{HashMap, hash_map::Iter}};
+    use cron::Schedule;
+    use std::{fmt, io::Cursor, str::{self, FromStr}, collections::{BTreeMap, BTreeSet, HashMap, HashSet, VecDeque, hash_map::Iter}, sync::atomic::{AtomicU64, Ordering}, time::Duration};
+    use tokio::task::JoinHandle;
     use tui::{widgets::{ListItem, ListState, TableState}, style::{Modifier, Style}};
 
+    /// Counts up the keys handed out to nodes
+    static NODE_KEYS: AtomicU64 = AtomicU64::new(1);
+
+    /// Returns a new key telling a node apart from all others while the app is
+    /// running. Unlike the ID every node has one right away, unlike its path it
+    /// stays the same when the node is moved or merged
+    fn new_node_key() -> u64 {
+        NODE_KEYS.fetch_add(1, Ordering::Relaxed)
+    }
+
+    /// A node inside the registry, either a single entry or a directory
+    /// grouping further nodes
     #[derive(Debug, Clone)]
-    enum RegistryNode {
+    pub enum RegistryNode {
         Element(EntryNode),
         Directory(DirectoryNode),
     }
@@ -303,614 +694,2944 @@ pub(crate) mod data_types {
     */
 
     impl RegistryNode {
-        /// Returns the Name of the node if available
-        fn name(&self) -> Option<String> {
+        /// Returns the ID of the node
+        pub fn id(&self) -> Option<u16> {
             match self {
-                Self::Element(e) => Some(e.to_string()),
-                Self::Directory(d) => None
+                Self::Element(e) => e.id,
+                Self::Directory(d) => d.id
             }
         }
 
-        /// Returns the ID of the node
-        fn id(&self) -> Option<u16> {
+        /// Returns the key telling the node apart from all others
+        fn key(&self) -> u64 {
             match self {
-                Self::Element(e) => e.id,
-                Self::Directory(d) => d.id
+                Self::Element(e) => e.key,
+                Self::Directory(d) => d.key,
+            }
+        }
+
+        /// Sets the ID of a node that was synced in the meantime
+        fn keep_id(&mut self, id: Option<u16>) {
+            match self {
+                Self::Element(e) => e.id = e.id.or(id),
+                Self::Directory(d) => d.id = d.id.or(id),
+            }
+        }
+
+        /// Returns a copy of the node, leaving out the content of a directory
+        fn shallow_clone(&self) -> RegistryNode {
+            match self {
+                Self::Element(e) => Self::Element(e.clone()),
+                Self::Directory(d) => Self::Directory(DirectoryNode {
+                    id: d.id,
+                    key: d.key,
+                    nodes: d.nodes.clone(),
+                    content: Vec::new(),
+                    expanded: d.expanded,
+                    removed: d.removed,
+                    modified: d.modified,
+                    moved: d.moved,
+                }),
             }
         }
 
         /// Returns whether the node was removed
-        fn removed(&self) -> bool {
+        pub fn removed(&self) -> bool {
             match self {
                 Self::Element(e) => e.removed,
-                Self::Directory(_) => false,
+                Self::Directory(d) => d.removed,
+            }
+        }
+
+        /// Marks the node as removed
+        pub fn remove(&mut self) {
+            match self {
+                Self::Element(e) => e.removed = true,
+                Self::Directory(d) => d.removed = true,
+            }
+        }
+
+        /// Returns whether the node was moved to another directory
+        pub fn is_moved(&self) -> bool {
+            match self {
+                Self::Element(e) => e.moved,
+                Self::Directory(d) => d.moved,
+            }
+        }
+
+        /// Sets whether the node was moved to another directory
+        pub fn set_moved(&mut self, moved: bool) {
+            match self {
+                Self::Element(e) => e.moved = moved,
+                Self::Directory(d) => d.moved = moved,
+            }
+        }
+
+        /// Generates a new ID for the node, see EntryNode::generate_id
+        pub fn generate_id(&mut self, existing_ids: &mut Vec<u16>) -> u16 {
+            match self {
+                Self::Element(e) => e.generate_id(existing_ids),
+                Self::Directory(d) => d.generate_id(existing_ids),
+            }
+        }
+
+        /// Returns all attributes of the node
+        pub fn nodes(&mut self) -> &mut HashMap<NodeName, NodeValue> {
+            match self {
+                Self::Element(e) => e.nodes(),
+                Self::Directory(d) => &mut d.nodes,
+            }
+        }
+
+        /// Returns the attributes of the node
+        pub fn attributes(&self) -> &HashMap<NodeName, NodeValue> {
+            match self {
+                Self::Element(e) => &e.nodes,
+                Self::Directory(d) => &d.nodes,
+            }
+        }
+
+        /// Returns the title of the node if available
+        pub fn title(&self) -> Option<String> {
+            match self {
+                Self::Element(e) => e.title(),
+                Self::Directory(d) => d.title(),
+            }
+        }
+
+        /// Sets this node to modified
+        pub fn modified(&mut self) {
+            match self {
+                Self::Element(e) => e.modified(),
+                Self::Directory(d) => d.modified = true,
+            }
+        }
+
+        /// Returns whether the node was modified since the last sync
+        pub fn is_modified(&self) -> bool {
+            match self {
+                Self::Element(e) => e.modified,
+                Self::Directory(d) => d.modified,
+            }
+        }
+
+        /// Sets this node to be in sync with the server
+        pub fn unmodified(&mut self) {
+            match self {
+                Self::Element(e) => e.modified = false,
+                Self::Directory(d) => d.modified = false,
             }
         }
 
-        /// Returns the AppElement if it is an Element or None
-        fn element(&self) -> Option<&EntryNode> {
+        /// Returns the position of the node in the manual order if it has one
+        pub fn position(&self) -> Option<u32> {
+            self.attributes().get(&NodeName::Position)?.to_string().trim().parse::<u32>().ok()
+        }
+
+        /// Returns the AppElement as mutable if it is an Element or None
+        pub fn element_mut(&mut self) -> Option<&mut EntryNode> {
             match self {
                 Self::Element(e) => Some(e),
                 _ => None,
             }
         }
+
+        /// Returns the DirectoryNode if it is a Directory or None
+        pub fn directory(&self) -> Option<&DirectoryNode> {
+            match self {
+                Self::Directory(d) => Some(d),
+                _ => None,
+            }
+        }
+
+        /// Returns the DirectoryNode as mutable if it is a Directory or None
+        pub fn directory_mut(&mut self) -> Option<&mut DirectoryNode> {
+            match self {
+                Self::Directory(d) => Some(d),
+                _ => None,
+            }
+        }
+
+        /// Returns the attributes of the node, see EntryNode::get_vecs
+        pub fn get_vecs(&self) -> Vec<(String, String)> {
+            match self {
+                Self::Element(e) => e.get_vecs(),
+                Self::Directory(d) => d.get_vecs(),
+            }
+        }
+
+        /// Returns the values of all attributes as a single lowercase string
+        pub fn get_text(&self) -> String {
+            match self {
+                Self::Element(e) => e.get_text(),
+                Self::Directory(d) => d.get_text(),
+            }
+        }
+
+        /// Returns whether any attribute of the node contains the search text,
+        /// ignoring the case
+        pub fn matches(&self, search: &str) -> bool {
+            self.get_text().contains(&search.to_lowercase())
+        }
+
+        /// Returns the number of attributes and subattributes of the node
+        pub fn flattened_node_count(&self) -> usize {
+            self.get_vecs().len()
+        }
+
+        /// Returns the node as an ListItem indented by the given depth
+        pub fn to_list_item<'a>(&self, depth: usize) -> ListItem<'a> {
+            match self {
+                Self::Element(e) => e.to_list_item(depth),
+                Self::Directory(d) => d.to_list_item(depth),
+            }
+        }
     }
 
     #[derive(Debug, Clone)]
-    struct Registry {
-        nodes: Vec<RegistryNode>,
+    pub struct Registry {
+        nodes: HashMap<NodeName, NodeValue>,
+        content: Vec<RegistryNode>,
+    }
+
+    impl Node for Registry {
+        /// Writes the whole registry using the given quick xml writer, nodes
+        /// without an ID or marked as removed are skipped.
+        /// Skips the outer 'registry' tags if 'with_head' is false
+        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error> {
+            if with_head {
+                writer.write_event(Event::Start(BytesStart::new("registry")))?;
+            }
+
+            write_attributes(writer, &self.nodes)?;
+
+            for node in self.content.iter().filter(|n| !n.removed()) {
+                node.write(writer, true)?;
+            }
+
+            if with_head {
+                writer.write_event(Event::End(BytesEnd::new("registry")))?;
+            }
+
+            Ok(())
+        }
+
+        fn write_state<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
+            writer.write_event(Event::Start(BytesStart::new("registry")))?;
+            write_attributes(writer, &self.nodes)?;
+            for node in self.content.iter() {
+                node.write_state(writer)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("registry")))?;
+            Ok(())
+        }
     }
 
     impl Registry {
-        fn empty() -> Self {
-            Self { nodes: Vec::new() }
+        pub fn empty() -> Self {
+            Self { nodes: HashMap::new(), content: Vec::new() }
         }
 
-        /// Parses the xml document as a String into the Registry object
-        fn from_string(mut self, xml: &String) -> Result<Self, quick_xml::Error> {
+        /// Serializes the registry into a xml document as a String
+        pub fn to_xml(&self) -> Result<String, quick_xml::Error> {
+            let mut writer = Writer::new(Cursor::new(Vec::new()));
+            self.write(&mut writer, true)?;
+            Ok(String::from_utf8_lossy(&writer.into_inner().into_inner()).to_string())
+        }
+
+        /// Parses the xml document as a String into the Registry object, fails
+        /// if the document does not contain a registry
+        pub fn from_string(xml: &str) -> Result<Self, quick_xml::Error> {
             let mut reader: Reader<&[u8]> = Reader::from_str(xml);
             reader.trim_text(true);
 
-            let mut in_element: Option<u16> = None;
-            let mut in_directory: Option<u16> = None;
-            //let mut inside: String = "".to_string();
-            let mut next_map: HashMap<NodeName, NodeValue> = HashMap::new();
-            let mut next_nodes: Vec<RegistryNode> = Vec::new();
             let mut buf: Vec<u8> = Vec::new();
 
             loop {
                 match reader.read_event_into(&mut buf)? {
-                    Event::Start(e) if e.name().as_ref() == b"entry" => {
-                        in_element = data_helpers::get_id_attribute(&reader, &e);
+                    Event::Start(e) if e.name().as_ref() == b"registry" => {
+                        let (nodes, content) = Self::read_content(&mut reader, b"registry", false)?;
+                        return Ok(Self { nodes, content });
                     }
-                    Event::End(e) if e.name().as_ref() == b"entry" => {
-                        if in_directory.is_some() {
-                            next_nodes.push(RegistryNode::Element(EntryNode::new(in_element, next_map.clone())));
-                            next_map.clear();
-                            in_element = None;
-                        } else if in_element.is_some() {
-                            self.nodes.push(RegistryNode::Element(EntryNode::new(in_element, next_map.clone())));
-                            next_map.clear();
-                            in_element = None;
-                        }
-
+                    Event::Empty(e) if e.name().as_ref() == b"registry" => {
+                        return Ok(Self::empty());
                     }
-                    Event::Start(e) if e.name().as_ref() == b"directory" => {
-                        in_directory = data_helpers::get_id_attribute(&reader, &e);
+                    Event::Eof => return Err(quick_xml::Error::UnexpectedEof("registry".to_string())),
+                    _ => (),
+                }
+            }
+        }
+
+        /// Restores the local state of the node from the attributes of its tag
+        fn read_state(reader: &Reader<&[u8]>, element: &BytesStart<'_>, node: &mut RegistryNode) {
+            let flag = |name: &[u8]| data_helpers::get_flag_attribute(reader, element, name);
+            match node {
+                RegistryNode::Element(e) => {
+                    (e.removed, e.modified, e.moved) = (flag(b"removed"), flag(b"modified"), flag(b"moved"));
+                },
+                RegistryNode::Directory(d) => {
+                    (d.removed, d.modified, d.moved) = (flag(b"removed"), flag(b"modified"), flag(b"moved"));
+                    d.expanded = flag(b"expanded");
+                },
+            }
+        }
+
+        /// Reads all entries and directories until the closing tag of parent is
+        /// reached. Returns the attributes found on this level alongside the nodes.
+        /// Restores the local state of the nodes if 'with_state' is true
+        fn read_content(reader: &mut Reader<&[u8]>, parent: &[u8], with_state: bool) -> Result<(HashMap<NodeName, NodeValue>, Vec<RegistryNode>), quick_xml::Error> {
+            let mut buf: Vec<u8> = Vec::new();
+
+            let mut attributes: HashMap<NodeName, NodeValue> = HashMap::new();
+            let mut nodes: Vec<RegistryNode> = Vec::new();
+
+            loop {
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) if data_helpers::is_registry_node(&e) => {
+                        let id: Option<u16> = data_helpers::get_id_attribute(reader, &e);
+                        let (node_attributes, content) = Self::read_content(reader, e.name().as_ref(), with_state)?;
+                        let mut node: RegistryNode = match e.name().as_ref() {
+                            b"directory" => RegistryNode::Directory(DirectoryNode::new(id, node_attributes, content)),
+                            _ => RegistryNode::Element(EntryNode::new(id, node_attributes)),
+                        };
+                        if with_state {
+                            Self::read_state(reader, &e, &mut node);
+                        }
+                        nodes.push(node);
                     }
-                    Event::End(e) if e.name().as_ref() == b"directory" => {
-                        if in_directory.is_some() {
-                            self.nodes.push(
-                                RegistryNode::Directory(
-                                    DirectoryNode::new(in_directory, next_map.clone(), next_nodes.clone())
-                                )
-                            );
-                            next_map.clear();
-                            next_nodes.clear();
-                            in_directory = None;
+                    Event::Empty(e) if data_helpers::is_registry_node(&e) => {
+                        let id: Option<u16> = data_helpers::get_id_attribute(reader, &e);
+                        let mut node: RegistryNode = match e.name().as_ref() {
+                            b"directory" => RegistryNode::Directory(DirectoryNode::new(id, HashMap::new(), Vec::new())),
+                            _ => RegistryNode::Element(EntryNode::new(id, HashMap::new())),
+                        };
+                        if with_state {
+                            Self::read_state(reader, &e, &mut node);
                         }
+                        nodes.push(node);
                     }
-                    Event::Start(e) if in_element.is_some() => {
-                        let found: NodeValue;
-                        found = data_helpers::read_node(&mut reader, e.name().as_ref())?;
-                        next_map.insert(
+                    Event::Start(e) => {
+                        let found: NodeValue = data_helpers::read_node(reader, e.name().as_ref())?;
+                        attributes.insert(
                             NodeName::from_str(str::from_utf8(e.name().as_ref()).unwrap_or("")),
                             found
                         );
                     }
-
-                    Event::End(e) if e.name().as_ref() == b"registry" => break,
+                    Event::Empty(e) => {
+                        attributes.insert(
+                            NodeName::from_str(str::from_utf8(e.name().as_ref()).unwrap_or("")),
+                            NodeValue::Text("".to_string())
+                        );
+                    }
+                    Event::End(e) if e.name().as_ref() == parent => break,
                     Event::Eof => break,
                     _ => (),
                 }
             }
 
-            Ok(self)
+            Ok((attributes, nodes))
         }
 
-        fn entries(&self) -> Vec<&EntryNode> {
-            self.nodes
+        fn collect_entries<'a>(nodes: &'a [RegistryNode], entries: &mut Vec<&'a EntryNode>) {
+            nodes
                 .iter()
-                .map(|e| e.element())
-                .filter(|e| e.is_some())
-                .map(|e| e.unwrap())
-                .collect()
+                .for_each(|n| match n {
+                    RegistryNode::Element(e) => entries.push(e),
+                    RegistryNode::Directory(d) => Self::collect_entries(&d.content, entries),
+                });
         }
-    }
 
+        /// Returns all entries of the registry, including the ones inside of
+        /// directories
+        pub fn entries(&self) -> Vec<&EntryNode> {
+            let mut entries: Vec<&EntryNode> = Vec::new();
+            Self::collect_entries(&self.content, &mut entries);
+            entries
+        }
+
+        /// Returns the IDs of all entries and directories in the registry
+        pub fn ids(&self) -> Vec<u16> {
+            let mut ids: Vec<u16> = Vec::new();
+            Self::for_each_node(&self.content, &mut |n| {
+                if let Some(id) = n.id() {
+                    ids.push(id);
+                }
+            });
+            ids
+        }
+
+        /// Returns the node found by following the path of indices through
+        /// the directories
+        pub fn node(&self, path: &[usize]) -> Option<&RegistryNode> {
+            let (last, parents) = path.split_last()?;
+            let mut nodes: &Vec<RegistryNode> = &self.content;
+            for i in parents {
+                match nodes.get(*i)? {
+                    RegistryNode::Directory(d) => nodes = &d.content,
+                    _ => return None,
+                }
+            }
+            nodes.get(*last)
+        }
+
+        /// Returns the node found by following the path of indices as mutable
+        pub fn node_mut(&mut self, path: &[usize]) -> Option<&mut RegistryNode> {
+            let (last, parents) = path.split_last()?;
+            self.content_mut(parents)?.get_mut(*last)
+        }
+
+        /// Returns the content of the directory found by following the path,
+        /// the top level of the registry is returned for an empty path
+        pub fn content(&self, path: &[usize]) -> Option<&Vec<RegistryNode>> {
+            let mut nodes: &Vec<RegistryNode> = &self.content;
+            for i in path {
+                match nodes.get(*i)? {
+                    RegistryNode::Directory(d) => nodes = &d.content,
+                    _ => return None,
+                }
+            }
+            Some(nodes)
+        }
+
+        /// Returns the content of the directory found by following the path
+        /// as mutable, the top level of the registry is returned for an empty path
+        pub fn content_mut(&mut self, path: &[usize]) -> Option<&mut Vec<RegistryNode>> {
+            let mut nodes: &mut Vec<RegistryNode> = &mut self.content;
+            for i in path {
+                match nodes.get_mut(*i)? {
+                    RegistryNode::Directory(d) => nodes = &mut d.content,
+                    _ => return None,
+                }
+            }
+            Some(nodes)
+        }
+
+        fn collect_visible(nodes: &[RegistryNode], prefix: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>) {
+            nodes
+                .iter()
+                .enumerate()
+                .for_each(|(i, n)| {
+                    prefix.push(i);
+                    paths.push(prefix.clone());
+                    if let RegistryNode::Directory(d) = n {
+                        if d.expanded {
+                            Self::collect_visible(&d.content, prefix, paths);
+                        }
+                    }
+                    prefix.pop();
+                });
+        }
+
+        /// Returns the paths of all nodes that are currently visible, only
+        /// descending into expanded directories
+        pub fn visible_paths(&self) -> Vec<Vec<usize>> {
+            let mut paths: Vec<Vec<usize>> = Vec::new();
+            Self::collect_visible(&self.content, &mut Vec::new(), &mut paths);
+            paths
+        }
+
+        fn collect_matching<F: Fn(&RegistryNode) -> bool>(nodes: &[RegistryNode], prefix: &mut Vec<usize>, paths: &mut Vec<Vec<usize>>, f: &F) -> bool {
+            let mut any: bool = false;
+            nodes
+                .iter()
+                .enumerate()
+                .for_each(|(i, n)| {
+                    prefix.push(i);
+                    let indx: usize = paths.len();
+                    let content_matches: bool = match n {
+                        RegistryNode::Directory(d) => Self::collect_matching(&d.content, prefix, paths, f),
+                        RegistryNode::Element(_) => false,
+                    };
+                    if content_matches || f(n) {
+                        paths.insert(indx, prefix.clone());
+                        any = true;
+                    }
+                    prefix.pop();
+                });
+            any
+        }
+
+        /// Returns the paths of all nodes matching f and of the directories
+        /// containing them, regardless of whether they are expanded
+        pub fn matching_paths<F: Fn(&RegistryNode) -> bool>(&self, f: &F) -> Vec<Vec<usize>> {
+            let mut paths: Vec<Vec<usize>> = Vec::new();
+            Self::collect_matching(&self.content, &mut Vec::new(), &mut paths, f);
+            paths
+        }
+
+        /// Orders the paths so the nodes inside of every directory are sorted
+        /// by cmp, keeping every directory in front of its content
+        pub fn sort_paths<F: Fn(&RegistryNode, &RegistryNode) -> std::cmp::Ordering>(&self, paths: &mut Vec<Vec<usize>>, cmp: &F) {
+            // The rank of every node among its siblings, by the path of its parent
+            let mut ranks: HashMap<Vec<usize>, Vec<usize>> = HashMap::new();
+            let mut keyed: Vec<(Vec<usize>, Vec<usize>)> = paths
+                .drain(..)
+                .map(|path| {
+                    let key: Vec<usize> = (0..path.len())
+                        .map(|level| {
+                            let rank: &Vec<usize> = ranks
+                                .entry(path[..level].to_vec())
+                                .or_insert_with(|| {
+                                    let content: &[RegistryNode] = self.content(&path[..level]).map(|c| c.as_slice()).unwrap_or(&[]);
+                                    let mut order: Vec<usize> = (0..content.len()).collect();
+                                    order.sort_by(|a, b| cmp(&content[*a], &content[*b]));
+                                    let mut rank: Vec<usize> = vec![0; content.len()];
+                                    order.iter().enumerate().for_each(|(r, i)| rank[*i] = r);
+                                    rank
+                                });
+                            rank.get(path[level]).copied().unwrap_or(usize::MAX)
+                        })
+                        .collect();
+                    (key, path)
+                })
+                .collect();
+            keyed.sort_by(|a, b| a.0.cmp(&b.0));
+            paths.extend(keyed.into_iter().map(|(_, path)| path));
+        }
+
+        /// Appends the node to the top level of the registry
+        pub fn push(&mut self, node: RegistryNode) {
+            self.content.push(node);
+        }
+
+        /// Calls f on every node of the given nodes, descending into directories
+        /// after their own call
+        fn for_each_node<'a, F: FnMut(&'a RegistryNode)>(nodes: &'a [RegistryNode], f: &mut F) {
+            nodes
+                .iter()
+                .for_each(|n| {
+                    f(n);
+                    if let RegistryNode::Directory(d) = n {
+                        Self::for_each_node(&d.content, f);
+                    }
+                });
+        }
+
+        /// Calls f on every node of the given nodes as mutable, descending into
+        /// directories after their own call
+        pub fn for_each_node_mut<F: FnMut(&mut RegistryNode)>(nodes: &mut [RegistryNode], f: &mut F) {
+            nodes
+                .iter_mut()
+                .for_each(|n| {
+                    f(n);
+                    if let RegistryNode::Directory(d) = n {
+                        Self::for_each_node_mut(&mut d.content, f);
+                    }
+                });
+        }
+
+        /// Finds and returns any node defined by its id
+        pub fn find(&self, id: u16) -> Option<&RegistryNode> {
+            let mut found: Option<&RegistryNode> = None;
+            Self::for_each_node(&self.content, &mut |n| {
+                if found.is_none() && n.id() == Some(id) {
+                    found = Some(n);
+                }
+            });
+            found
+        }
+
+        fn find_in_mut(nodes: &mut [RegistryNode], id: u16) -> Option<&mut RegistryNode> {
+            for n in nodes.iter_mut() {
+                if n.id() == Some(id) {
+                    return Some(n);
+                }
+                if let RegistryNode::Directory(d) = n {
+                    if let Some(found) = Self::find_in_mut(&mut d.content, id) {
+                        return Some(found);
+                    }
+                }
+            }
+            None
+        }
+
+        /// Finds and returns any node defined by its id as mutable
+        pub fn find_mut(&mut self, id: u16) -> Option<&mut RegistryNode> {
+            Self::find_in_mut(&mut self.content, id)
+        }
+
+        fn take_node_from(nodes: &mut Vec<RegistryNode>, id: u16) -> Option<RegistryNode> {
+            if let Some(pos) = nodes.iter().position(|n| n.id() == Some(id)) {
+                return Some(nodes.remove(pos));
+            }
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .find_map(|d| Self::take_node_from(&mut d.content, id))
+        }
+
+        /// Keeps only the nodes changed locally, the changed nodes inside of
+        /// unchanged directories take the place of their directory
+        fn keep_changed(nodes: Vec<RegistryNode>) -> Vec<RegistryNode> {
+            nodes
+                .into_iter()
+                .flat_map(|mut n| {
+                    let content: Vec<RegistryNode> = n.directory_mut().map(|d| std::mem::take(&mut d.content)).unwrap_or_default();
+                    let kept: Vec<RegistryNode> = Self::keep_changed(content);
+                    match !n.removed() && (n.is_modified() || n.is_moved() || n.id().is_none()) {
+                        true => {
+                            n.set_moved(true);
+                            if let Some(directory) = n.directory_mut() {
+                                directory.content = kept;
+                            }
+                            vec![n]
+                        },
+                        false => kept,
+                    }
+                })
+                .collect()
+        }
+
+        fn drop_node_from(nodes: &mut Vec<RegistryNode>, id: u16) -> bool {
+            if let Some(pos) = nodes.iter().position(|n| n.id() == Some(id)) {
+                let mut node: RegistryNode = nodes.remove(pos);
+                let content: Vec<RegistryNode> = node.directory_mut().map(|d| std::mem::take(&mut d.content)).unwrap_or_default();
+                nodes.splice(pos..pos, Self::keep_changed(content).into_iter().map(|mut n| {
+                    n.set_moved(true);
+                    n
+                }));
+                return true;
+            }
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .any(|d| Self::drop_node_from(&mut d.content, id))
+        }
+
+        /// Removes the node with the given id wherever it is located, but the
+        /// nodes inside of it that were changed locally are moved up to its
+        /// place instead of being removed as well. Returns whether a node was removed
+        pub fn drop_node(&mut self, id: u16) -> bool {
+            Self::drop_node_from(&mut self.content, id)
+        }
+
+        fn find_key_in_mut(nodes: &mut [RegistryNode], key: u64) -> Option<&mut RegistryNode> {
+            for n in nodes.iter_mut() {
+                if n.key() == key {
+                    return Some(n);
+                }
+                if let RegistryNode::Directory(d) = n {
+                    if let Some(found) = Self::find_key_in_mut(&mut d.content, key) {
+                        return Some(found);
+                    }
+                }
+            }
+            None
+        }
+
+        fn take_key_from(nodes: &mut Vec<RegistryNode>, key: u64) -> Option<RegistryNode> {
+            if let Some(pos) = nodes.iter().position(|n| n.key() == key) {
+                return Some(nodes.remove(pos));
+            }
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .find_map(|d| Self::take_key_from(&mut d.content, key))
+        }
+
+        fn collect_placements(nodes: &[RegistryNode], parent: Option<u64>, depth: usize, placements: &mut HashMap<u64, Placement>) {
+            nodes
+                .iter()
+                .enumerate()
+                .for_each(|(index, n)| {
+                    placements.insert(n.key(), Placement { node: n.shallow_clone(), parent, depth, index });
+                    if let RegistryNode::Directory(d) = n {
+                        Self::collect_placements(&d.content, Some(d.key), depth + 1, placements);
+                    }
+                });
+        }
+
+        /// Returns every node without its content along with where it is
+        /// located, by the key of the node
+        fn placements(&self) -> HashMap<u64, Placement> {
+            let mut placements: HashMap<u64, Placement> = HashMap::new();
+            Self::collect_placements(&self.content, None, 0, &mut placements);
+            placements
+        }
+
+        /// Puts the nodes of the changes back into the state they were in
+        /// before, nodes that did not exist yet are taken out again. Synced
+        /// nodes are marked removed instead, as they exist on the server, and
+        /// are marked to be synced again if the registry was merged since
+        fn revert(&mut self, changes: &[NodeChange], merged: bool) {
+            let current: HashMap<u64, Placement> = self.placements();
+
+            // Nodes are taken out before the directories they are located in
+            let mut ordered: Vec<&NodeChange> = changes.iter().collect();
+            ordered.sort_by_key(|c| std::cmp::Reverse(current.get(&c.key).map(|p| p.depth)));
+            let mut taken: HashMap<u64, RegistryNode> = HashMap::new();
+            ordered
+                .iter()
+                .for_each(|c| {
+                    if let Some(node) = Self::take_key_from(&mut self.content, c.key) {
+                        taken.insert(c.key, node);
+                    }
+                });
+
+            let mut targets: Vec<(Placement, Vec<RegistryNode>)> = Vec::new();
+            for change in ordered {
+                let node: Option<RegistryNode> = taken.remove(&change.key);
+                let (mut placement, content): (Placement, Vec<RegistryNode>) = match (&change.before, node) {
+                    (Some(before), Some(mut node)) => {
+                        let mut placement: Placement = before.clone();
+                        placement.node.keep_id(node.id());
+                        if let (Some(d), Some(current_d)) = (placement.node.directory_mut(), node.directory_mut()) {
+                            d.expanded = current_d.expanded;
+                        }
+                        let content: Vec<RegistryNode> = node.directory_mut().map(|d| std::mem::take(&mut d.content)).unwrap_or_default();
+                        (placement, content)
+                    },
+                    (Some(before), None) => (before.clone(), Vec::new()),
+                    (None, Some(mut node)) if node.id().is_some() => {
+                        let mut placement: Placement = current[&change.key].clone();
+                        placement.node.remove();
+                        let content: Vec<RegistryNode> = node.directory_mut().map(|d| std::mem::take(&mut d.content)).unwrap_or_default();
+                        (placement, content)
+                    },
+                    // Anything left in a directory that is taken out takes its place
+                    (None, Some(mut node)) => {
+                        let placement: &Placement = &current[&change.key];
+                        let content: Vec<RegistryNode> = node.directory_mut().map(|d| std::mem::take(&mut d.content)).unwrap_or_default();
+                        content
+                            .into_iter()
+                            .enumerate()
+                            .for_each(|(i, n)| targets.push((
+                                Placement { node: n, parent: placement.parent, depth: placement.depth, index: placement.index + i },
+                                Vec::new()
+                            )));
+                        continue;
+                    },
+                    (None, None) => continue,
+                };
+                if merged && placement.node.id().is_some() {
+                    placement.node.modified();
+                    placement.node.set_moved(true);
+                }
+                targets.push((placement, content));
+            }
+
+            // Directories are put back before the nodes located in them
+            targets.sort_by_key(|(p, _)| (p.depth, p.index));
+            for (placement, content) in targets {
+                let mut node: RegistryNode = placement.node;
+                if let Some(d) = node.directory_mut() {
+                    d.content.extend(content);
+                }
+                let level: &mut Vec<RegistryNode> = match placement.parent.and_then(|k| Self::find_key_in_mut(&mut self.content, k)) {
+                    Some(RegistryNode::Directory(d)) => &mut d.content,
+                    _ => &mut self.content,
+                };
+                level.insert(placement.index.min(level.len()), node);
+            }
+        }
+
+        /// Returns the nodes that differ between the placements before and
+        /// after a change
+        fn changes_between(before: &HashMap<u64, Placement>, after: &HashMap<u64, Placement>) -> Vec<NodeChange> {
+            let keys: HashSet<u64> = before.keys().chain(after.keys()).copied().collect();
+            keys
+                .into_iter()
+                .filter(|key| match (before.get(key), after.get(key)) {
+                    (Some(b), Some(a)) => !b.same_as(a),
+                    _ => true,
+                })
+                .map(|key| NodeChange {
+                    key,
+                    before: before.get(&key).cloned(),
+                })
+                .collect()
+        }
+
+        fn collect_parents(nodes: &[RegistryNode], parent: Option<u16>, parents: &mut HashMap<u16, Option<u16>>) {
+            nodes
+                .iter()
+                .for_each(|n| {
+                    if let Some(id) = n.id() {
+                        parents.insert(id, parent);
+                    }
+                    if let RegistryNode::Directory(d) = n {
+                        if let Some(directory_id) = d.id {
+                            Self::collect_parents(&d.content, Some(directory_id), parents);
+                        }
+                    }
+                });
+        }
+
+        /// Returns the ID of the directory every node is located in, None for
+        /// nodes on the top level. Nodes inside of directories without an ID
+        /// are left out
+        pub fn parents(&self) -> HashMap<u16, Option<u16>> {
+            let mut parents: HashMap<u16, Option<u16>> = HashMap::new();
+            Self::collect_parents(&self.content, None, &mut parents);
+            parents
+        }
+
+        /// Moves the node with the given id to the end of the directory with
+        /// the id parent or of the top level for None. Returns false without
+        /// moving anything if either is missing, the directory was removed or
+        /// the directory is located inside of the node
+        pub fn move_node(&mut self, id: u16, parent: Option<u16>) -> bool {
+            let Some(node) = self.find(id) else {
+                return false;
+            };
+            if let Some(parent_id) = parent {
+                let mut inside: bool = parent_id == id;
+                if let RegistryNode::Directory(d) = node {
+                    Self::for_each_node(&d.content, &mut |n| inside = inside || n.id() == Some(parent_id));
+                }
+                if inside || !self.find(parent_id).is_some_and(|p| p.directory().is_some() && !p.removed()) {
+                    return false;
+                }
+            }
+            let Some(node) = Self::take_node_from(&mut self.content, id) else {
+                return false;
+            };
+            match parent.and_then(|p| self.find_mut(p)).and_then(|p| p.directory_mut()) {
+                Some(directory) => directory.content.push(node),
+                None => self.content.push(node),
+            }
+            true
+        }
+
+        fn drop_unsynced_removed(nodes: &mut Vec<RegistryNode>) {
+            nodes.retain(|n| !(n.removed() && n.id().is_none()));
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .for_each(|d| Self::drop_unsynced_removed(&mut d.content));
+        }
+
+        /// Drops all removed nodes that were never synced and therefore
+        /// don't need to be deleted on the server
+        pub fn remove_unsynced(&mut self) {
+            Self::drop_unsynced_removed(&mut self.content);
+        }
+
+        /// Generates IDs for all nodes that don't already have one, avoiding
+        /// the existing ids. Returns the newly generated ids
+        pub fn add_missing_ids(&mut self, existing_ids: &mut Vec<u16>) -> Vec<u16> {
+            let mut new_ids: Vec<u16> = Vec::new();
+            Self::for_each_node_mut(&mut self.content, &mut |n| {
+                if n.id().is_none() {
+                    new_ids.push(n.generate_id(existing_ids));
+                }
+            });
+            new_ids
+        }
+
+        /// Returns whether any node was created, removed, moved or edited locally
+        pub fn has_local_changes(&self) -> bool {
+            let mut changed: bool = false;
+            Self::for_each_node(&self.content, &mut |n| {
+                changed = changed || n.id().is_none() || n.removed() || n.is_moved() || n.is_modified();
+            });
+            changed
+        }
+
+        fn drop_pushed_removed(nodes: &mut Vec<RegistryNode>, pushed: &Registry) {
+            nodes.retain(|n| !(n.removed() && n.id().is_some_and(|id| pushed.find(id).is_some_and(|p| p.removed()))));
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .for_each(|d| Self::drop_pushed_removed(&mut d.content, pushed));
+        }
+
+        /// Drops all removed nodes and resets the moved and modified state of
+        /// all nodes that were pushed as they are in the pushed registry, except
+        /// for the nodes defined by the ids in keep_modified
+        pub fn clear_local_changes(&mut self, pushed: &Registry, keep_modified: &[u16]) {
+            Self::drop_pushed_removed(&mut self.content, pushed);
+            Self::for_each_node_mut(&mut self.content, &mut |n| {
+                let Some(id) = n.id() else {
+                    return;
+                };
+                let Some(p) = pushed.find(id) else {
+                    return;
+                };
+                if p.is_moved() {
+                    n.set_moved(false);
+                }
+                if !keep_modified.contains(&id) && p.attributes() == n.attributes() {
+                    n.unmodified();
+                }
+            });
+        }
+
+        /// Returns the nodes on the top level of the registry
+        pub fn top_level(&self) -> &Vec<RegistryNode> {
+            &self.content
+        }
+
+        /// Sets the position of all nodes to their index, marking the nodes
+        /// whose position changed as modified
+        pub fn number_positions(nodes: &mut [RegistryNode]) {
+            nodes
+                .iter_mut()
+                .enumerate()
+                .filter(|(i, n)| n.position() != Some(*i as u32))
+                .for_each(|(i, n)| {
+                    n.nodes().insert(NodeName::Position, NodeValue::Text(i.to_string()));
+                    n.modified();
+                });
+        }
+
+        fn sort_nodes_by_position(nodes: &mut [RegistryNode]) {
+            // Nodes without a position stay behind the node in front of them
+            let mut last: u32 = 0;
+            let keys: Vec<u32> = nodes
+                .iter()
+                .map(|n| {
+                    last = n.position().unwrap_or(last);
+                    last
+                })
+                .collect();
+            let mut keyed: Vec<(u32, RegistryNode)> = keys.into_iter().zip(nodes.iter().cloned()).collect();
+            keyed.sort_by_key(|(k, _)| *k);
+            nodes
+                .iter_mut()
+                .zip(keyed)
+                .for_each(|(n, (_, sorted))| *n = sorted);
+            nodes
+                .iter_mut()
+                .filter_map(|n| n.directory_mut())
+                .for_each(|d| Self::sort_nodes_by_position(&mut d.content));
+        }
+
+        /// Orders every level of the registry by the position of its nodes,
+        /// so the manual order is the same on every device
+        pub fn sort_by_position(&mut self) {
+            Self::sort_nodes_by_position(&mut self.content);
+        }
+
+        fn merge_new_nodes(&mut self, fetched: &[RegistryNode], parent: Option<u16>, known: &[u16]) {
+            fetched
+                .iter()
+                .for_each(|n| {
+                    if !n.id().is_some_and(|id| known.contains(&id)) {
+                        // The content is merged node by node, as it may contain known nodes
+                        let mut new: RegistryNode = n.clone();
+                        if let Some(directory) = new.directory_mut() {
+                            directory.content.clear();
+                        }
+                        match parent.and_then(|p| self.find_mut(p)).and_then(|p| p.directory_mut()) {
+                            Some(directory) => directory.content.push(new),
+                            None => self.content.push(new),
+                        }
+                    }
+                    if let RegistryNode::Directory(d) = n {
+                        self.merge_new_nodes(&d.content, d.id, known);
+                    }
+                });
+        }
+
+        /// Adds the nodes of the fetched registry that are not already known
+        /// anywhere, placing them in the directory they were fetched in
+        pub fn add_new_nodes(&mut self, fetched: &Registry) {
+            let known: Vec<u16> = self.ids();
+            self.merge_new_nodes(&fetched.content, None, &known);
+        }
+    }
+
+    /// A directory inside the registry grouping further nodes, it carries
+    /// attributes just like an entry does
+    #[derive(Debug, Clone)]
+    pub struct DirectoryNode {
+        id: Option<u16>,
+        key: u64,
+        nodes: HashMap<NodeName, NodeValue>,
+        content: Vec<RegistryNode>,
+        expanded: bool,
+        removed: bool,
+        modified: bool,
+        moved: bool,
+    }
+
+    impl Node for DirectoryNode {
+        /// Writes the directory and its content using the given quick xml writer
+        /// skips silently if the directory does not have an ID
+        /// Skips the outer 'directory' tags and the content if 'with_head' is false
+        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error> {
+            let Some(id) = self.id else {
+                return Ok(());
+            };
+            if with_head {
+                writer.write_event(Event::Start(
+                    BytesStart::new("directory")
+                        .with_attributes([Attribute::from(("id", id.to_string().as_str()))])
+                    )
+                )?;
+            }
+
+            write_attributes(writer, &self.nodes)?;
+
+            if with_head {
+                for node in self.content.iter().filter(|n| !n.removed()) {
+                    node.write(writer, true)?;
+                }
+                writer.write_event(Event::End(BytesEnd::new("directory")))?;
+            }
+
+            Ok(())
+        }
+
+        fn write_state<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
+            writer.write_event(Event::Start(state_head(
+                "directory",
+                self.id,
+                &[("removed", self.removed), ("modified", self.modified), ("moved", self.moved), ("expanded", self.expanded)]
+            )))?;
+            write_attributes(writer, &self.nodes)?;
+            for node in self.content.iter() {
+                node.write_state(writer)?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("directory")))?;
+            Ok(())
+        }
+    }
+
+    impl DirectoryNode {
+        fn new(id: Option<u16>, nodes: HashMap<NodeName, NodeValue>, content: Vec<RegistryNode>) -> Self {
+            Self {
+                id,
+                key: new_node_key(),
+                nodes,
+                content,
+                expanded: false,
+                removed: false,
+                modified: false,
+                moved: false,
+            }
+        }
+
+        /// Creates a new empty directory that was not synced yet
+        pub fn blank() -> Self {
+            Self::new(None, HashMap::new(), Vec::new())
+        }
+
+        /// Generates a new ID for this directory, see EntryNode::generate_id
+        pub fn generate_id(&mut self, existing_ids: &mut Vec<u16>) -> u16 {
+            let new_id: u16 = data_helpers::generate_id(existing_ids);
+            self.id = Some(new_id);
+            new_id
+        }
+
+        /// Returns the nodes inside of the directory
+        pub fn content(&self) -> &Vec<RegistryNode> {
+            &self.content
+        }
+
+        /// Returns the nodes inside of the directory as mutable
+        pub fn content_mut(&mut self) -> &mut Vec<RegistryNode> {
+            &mut self.content
+        }
+
+        /// Expands the directory so its content is listed
+        pub fn expand(&mut self) {
+            self.expanded = true;
+        }
+
+        pub fn title(&self) -> Option<String> {
+            Some(self.nodes.get(&NodeName::Title)?.to_string())
+        }
+
+        /// Expands the directory if collapsed and collapses it if expanded
+        pub fn toggle_expanded(&mut self) {
+            self.expanded = !self.expanded;
+        }
+
+        /// Returns the attributes of the directory, see EntryNode::get_vecs
+        pub fn get_vecs(&self) -> Vec<(String, String)> {
+            data_helpers::flatten_sorted(&self.nodes)
+        }
+
+        /// Returns the values of all attributes, see EntryNode::get_text
+        pub fn get_text(&self) -> String {
+            self.nodes.values().map(|e| e.to_string()).collect::<Vec<String>>().join(" ").to_lowercase()
+        }
+
+        /// Returns the DirectoryNode as an ListItem indented by the given depth
+        pub fn to_list_item<'a>(&self, depth: usize) -> ListItem<'a> {
+            let mut item = ListItem::new(format!(
+                "{}{} {}",
+                crate::DISPLAY_INDENT_STRING.repeat(depth),
+                if self.expanded { "v" } else { ">" },
+                self.title().unwrap_or("<no name>".to_string())
+            ));
+            if self.removed {
+                item = item.style(Style::default().add_modifier(Modifier::CROSSED_OUT));
+            } else if self.modified {
+                item = item.style(Style::default().add_modifier(Modifier::BOLD));
+            } else if self.id.is_none() {
+                item = item.style(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            item
+        }
+    }
+
+    /// The Keynames for different Nodes
+    #[derive(PartialEq, Eq, Hash, Debug, Clone)]
+    pub enum NodeName {
+        /// The title
+        Title,
+        /// A description
+        Description,
+        /// The Location aka. where it takes place
+        Location,
+        /// A color which can optionally be used for display purposes
+        Color,
+        /// A due date
+        Due,
+        /// How long it takes place in minutes
+        Duration,
+        /// How to trigger an alert
+        Alert,
+        /// Where the node is placed in the manual order of its directory
+        Position,
+        /// How the entry repeats, as a cron expression or daily, weekly,
+        /// monthly or yearly
+        Repeat,
+        /// When the entry was completed
+        Done,
+        /// Any other elements
+        Other(String),
+    }
+
+    impl fmt::Display for NodeName {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", match self {
+                Self::Title => "name",
+                Self::Description => "description",
+                Self::Location => "location",
+                Self::Color => "color",
+                Self::Due => "due",
+                Self::Duration => "duration",
+                Self::Alert => "alert",
+                Self::Position => "position",
+                Self::Repeat => "repeat",
+                Self::Done => "done",
+                Self::Other(val) => val,
+            })
+        }
+    }
+
+    impl NodeName {
+        pub fn from_str(s: &str) -> Self {
+            match s.to_lowercase().as_str() {
+                "name" => Self::Title,
+                "description" => Self::Description,
+                "location" => Self::Location,
+                "color" => Self::Color,
+                "due" => Self::Due,
+                "duration" => Self::Duration,
+                "alert" => Self::Alert,
+                "position" => Self::Position,
+                "repeat" => Self::Repeat,
+                "done" => Self::Done,
+                e => Self::Other(e.to_string())
+            }
+        }
+
+        pub fn order(&self) -> u8 {
+            match self {
+                Self::Title => 0,
+                Self::Description => 1,
+                Self::Location => 2,
+                Self::Due => 3,
+                Self::Duration => 4,
+                Self::Repeat => 5,
+                Self::Done => 6,
+                Self::Other(v) => 127 + (v.len() % 128).try_into().unwrap_or(0),
+                Self::Color => 254,
+                Self::Alert => 254,
+                Self::Position => 255,
+            }
+        }
+    }
+
+    trait Node {
+        // Writes the element using the given quick xml writer
+        /// skips silently if the element does not have an ID
+        /// Skips the outer 'entry' tags if 'with_head' is false
+        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error>;
+
+        /// Writes the element including its local state, that is not known to
+        /// the server, using the given quick xml writer. Nothing is skipped
+        fn write_state<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error>;
+    }
+
+    /// Creates the opening tag of a node carrying its id and all set flags
+    fn state_head<'a>(name: &'a str, id: Option<u16>, flags: &[(&str, bool)]) -> BytesStart<'a> {
+        let mut head: BytesStart<'a> = BytesStart::new(name);
+        if let Some(id) = id {
+            head.push_attribute(("id", id.to_string().as_str()));
+        }
+        flags
+            .iter()
+            .filter(|(_, set)| *set)
+            .for_each(|(flag, _)| head.push_attribute((*flag, "true")));
+        head
+    }
+
+    impl Node for RegistryNode {
+        /// Writes the entry or directory using the given quick xml writer
+        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error> {
+            match self {
+                Self::Element(e) => e.write(writer, with_head),
+                Self::Directory(d) => d.write(writer, with_head),
+            }
+        }
+
+        fn write_state<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
+            match self {
+                Self::Element(e) => e.write_state(writer),
+                Self::Directory(d) => d.write_state(writer),
+            }
+        }
+    }
+
+    /// Writes each attribute as its own tag using the given quick xml writer,
+    /// the attributes are written in their display order to keep the output stable
+    fn write_attributes<W: std::io::Write>(writer: &mut Writer<W>, nodes: &HashMap<NodeName, NodeValue>) -> Result<(), quick_xml::Error> {
+        let mut nodes_sorted: Vec<(&NodeName, &NodeValue)> = nodes.iter().collect();
+        nodes_sorted.sort_by_key(|(k, _)| (k.order(), k.to_string()));
+
+        for (key, value) in nodes_sorted {
+            writer.write_event(Event::Start(BytesStart::new(key.to_string())))?;
+            value.write_xml(writer)?;
+            writer.write_event(Event::End(BytesEnd::new(key.to_string())))?;
+        }
+        Ok(())
+    }
+
+    /// The Value of a subnode inside an entry node
+    /// aka. the text in between a subnode of an entry node
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum NodeValue {
+        Text(String),
+        NestedNode(HashMap<NodeName, NodeValue>),
+    }
+
+    /*
+    impl ToString for NodeValue {
+        fn to_string(&self) -> String {
+            match self {
+                Self::Text(t) => {
+                    t.to_string()
+                },
+                Self::NestedNode(n) => {
+                    n.iter()
+                        .map(|e| e.name().to_string())
+                        .collect::<Vec<String>>()
+                        .join(" ")
+                }
+            }
+        }
+    }
+    */
+
+    impl fmt::Display for NodeValue {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                NodeValue::Text(t) => {
+                    write!(f, "{}", t)
+                },
+                NodeValue::NestedNode(n) => {
+                    n
+                        .iter()
+                        .try_for_each(|(k, v)| {
+                            writeln!(f, "{} - {}", k, v)
+                        })
+                }
+            }
+        }
+    }
+
+    impl NodeValue {
+        /// Writes the Nodevalue into the provided xml writer
+        fn write_xml<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
+            match self {
+                Self::Text(t) => {
+                    writer.write_event(Event::Text(BytesText::new(t)))?;
+                },
+                Self::NestedNode(n) => {
+                    write_attributes(writer, n)?;
+                }
+            };
+
+            Ok(())
+        }
+    }
+
+    #[derive(Debug, Clone)]
+    pub struct EntryNode {
+        id: Option<u16>,
+        key: u64,
+        nodes: HashMap<NodeName, NodeValue>,
+        removed: bool,
+        modified: bool,
+        moved: bool,
+    }
+
+    impl PartialEq for EntryNode {
+        fn eq(&self, other: &Self) -> bool {
+            match self.id {
+                Some(id) => Some(id) == other.id,
+                None => self == other, // Isn't this recursive???
+            }
+        }
+    }
+
+    impl AsRef<Self> for EntryNode {
+        fn as_ref(&self) -> &Self {
+            self
+        }
+    }
+
+    impl fmt::Display for EntryNode {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            /*
+            let disp_due: String = match self.nodes.get("due") {
+                Some(due) => {
+                    let due_timestamp: i64 = due.parse().unwrap_or(-1);
+                    let utc_due: String = match Utc.timestamp_opt(due_timestamp, 0) {
+                        LocalResult::None => "None".to_string(),
+                        LocalResult::Single(val) => val.with_timezone(&chrono::Local).to_rfc2822(),
+                        LocalResult::Ambiguous(val, _) => val.with_timezone(&chrono::Local).to_rfc2822(),
+                    };
+                    utc_due
+                },
+                None => "None".to_string()
+            };
+            */
+        
+            let id: String = match self.id {
+                Some(id) => format!("{}", id),
+                None => "None".to_string()
+            };
+            write!(
+                f,
+                "ID: {}",
+                id,
+            )
+        }
+    }
+
+    impl Node for EntryNode {
+        /// Writes the element using the given quick xml writer
+        /// skips silently if the element does not have an ID
+        /// Skips the outer 'entry' tags if 'with_head' is false
+        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error> {
+            let Some(id) = self.id else {
+                return Ok(());
+            };
+            if with_head {
+                writer.write_event(Event::Start(
+                    BytesStart::new("entry")
+                        .with_attributes([Attribute::from(("id", id.to_string().as_str()))])
+                    )
+                )?;
+            }
+
+            write_attributes(writer, &self.nodes)?;
+
+            if with_head {
+                writer.write_event(Event::End(BytesEnd::new("entry")))?;
+            }
+
+            Ok(())
+        }
+
+        fn write_state<W: std::io::Write>(&self, writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
+            writer.write_event(Event::Start(state_head(
+                "entry",
+                self.id,
+                &[("removed", self.removed), ("modified", self.modified), ("moved", self.moved)]
+            )))?;
+            write_attributes(writer, &self.nodes)?;
+            writer.write_event(Event::End(BytesEnd::new("entry")))?;
+            Ok(())
+        }
+    }
+
+    impl EntryNode {
+        pub fn new(id: Option<u16>, nodes: HashMap<NodeName, NodeValue>) -> Self {
+            Self {
+                id,
+                key: new_node_key(),
+                nodes,
+                removed: false,
+                modified: false,
+                moved: false,
+            }
+        }
+
+        pub fn title(&self) -> Option<String> {
+            Some(self.nodes.get(&NodeName::Title)?.to_string())
+        }
+
+        pub fn description(&self) -> Option<String> {
+            Some(self.nodes.get(&NodeName::Description)?.to_string())
+        }
+
+        pub fn due(&self) -> Option<u32> {
+            self.nodes.get(&NodeName::Due)?
+                .to_string()
+                .parse::<u32>()
+                .ok()
+        }
+
+        /// Returns the number of nodes held by the EntryNode
+        pub fn node_count(&self) -> usize {
+            self.nodes.len()
+        }
+
+        /// Returns where the entry takes place if available
+        pub fn location(&self) -> Option<String> {
+            Some(self.nodes.get(&NodeName::Location)?.to_string())
+        }
+
+        /// Returns whether the entry was completed
+        pub fn is_done(&self) -> bool {
+            self.nodes
+                .get(&NodeName::Done)
+                .is_some_and(|v| !matches!(v.to_string().trim().to_lowercase().as_str(), "" | "0" | "false" | "no"))
+        }
+
+        /// Marks the entry as done at the given time or as not done. Completing
+        /// a recurring entry moves it on to its next occurrence instead
+        pub fn toggle_done(&mut self, now: DateTime<Local>) -> Completion {
+            if self.is_done() {
+                self.nodes.remove(&NodeName::Done);
+                self.modified = true;
+                Completion::Undone
+            } else if self.advance_recurrence() {
+                Completion::Advanced
+            } else {
+                self.nodes.insert(NodeName::Done, NodeValue::Text(now.timestamp().to_string()));
+                self.modified = true;
+                Completion::Done
+            }
+        }
+
+        /// Returns how an alert for the entry is triggered if available
+        pub fn alert_rule(&self) -> Option<AlertRule> {
+            AlertRule::parse(&self.nodes.get(&NodeName::Alert)?.to_string())
+        }
+
+        /// Returns the color the entry should be displayed in if available
+        pub fn color(&self) -> Option<String> {
+            Some(self.nodes.get(&NodeName::Color)?.to_string().trim().to_string())
+        }
+
+        /// Returns how long the entry takes place in minutes if available
+        pub fn duration(&self) -> Option<u32> {
+            self.nodes.get(&NodeName::Duration)?
+                .to_string()
+                .trim()
+                .parse::<u32>()
+                .ok()
+        }
+
+        /// Returns when the entry starts and ends in local time, entries
+        /// without a duration end when they start
+        pub fn span(&self) -> Option<(DateTime<Local>, DateTime<Local>)> {
+            let start: DateTime<Local> = match Local.timestamp_opt(self.due()? as i64, 0) {
+                LocalResult::Single(t) | LocalResult::Ambiguous(t, _) => t,
+                LocalResult::None => return None,
+            };
+            let end: DateTime<Local> = start + chrono::Duration::minutes(self.duration().unwrap_or(0) as i64);
+            Some((start, end))
+        }
+
+        /// Returns how the entry repeats if available
+        pub fn recurrence(&self) -> Option<Recurrence> {
+            Recurrence::parse(&self.nodes.get(&NodeName::Repeat)?.to_string())
+        }
+
+        /// Returns how long the entry blocks time, entries without a duration
+        /// block the minute they start in
+        fn blocked(start: DateTime<Local>, end: DateTime<Local>) -> chrono::Duration {
+            (end - start).max(chrono::Duration::minutes(1))
+        }
+
+        /// Returns the start and end of every occurrence blocking time between
+        /// from and to. Entries without recurrence occur once, at their due date
+        pub fn occurrences(&self, from: DateTime<Local>, to: DateTime<Local>) -> Vec<(DateTime<Local>, DateTime<Local>)> {
+            let Some((start, end)) = self.span() else {
+                return Vec::new();
+            };
+            let length: chrono::Duration = end - start;
+            let blocked: chrono::Duration = Self::blocked(start, end);
+            let Some(recurrence) = self.recurrence() else {
+                return match start < to && from < start + blocked {
+                    true => vec![(start, end)],
+                    false => Vec::new(),
+                };
+            };
+
+            let mut occurrences: Vec<(DateTime<Local>, DateTime<Local>)> = Vec::new();
+            let mut next: Option<DateTime<Local>> = recurrence.next_start(start, from - blocked + chrono::Duration::seconds(1));
+            while let Some(occurrence) = next {
+                if occurrence >= to || occurrences.len() >= crate::RECURRENCE_MAX_OCCURRENCES {
+                    break;
+                }
+                occurrences.push((occurrence, occurrence + length));
+                next = recurrence.next_start(start, occurrence + chrono::Duration::seconds(1));
+            }
+            occurrences
+        }
+
+        /// Returns the first occurrence that has not ended before now, entries
+        /// without recurrence always return their due date
+        pub fn upcoming(&self, now: DateTime<Local>) -> Option<(DateTime<Local>, DateTime<Local>)> {
+            let (start, end) = self.span()?;
+            let Some(recurrence) = self.recurrence() else {
+                return Some((start, end));
+            };
+            let next: DateTime<Local> = recurrence.next_start(start, now - Self::blocked(start, end) + chrono::Duration::seconds(1))?;
+            Some((next, next + (end - start)))
+        }
+
+        /// Moves the due date to the occurrence after it, returns false if the
+        /// entry does not repeat
+        pub fn advance_recurrence(&mut self) -> bool {
+            let (Some((start, _)), Some(recurrence)) = (self.span(), self.recurrence()) else {
+                return false;
+            };
+            let Some(next) = recurrence.next_start(start, start + chrono::Duration::seconds(1)) else {
+                return false;
+            };
+            self.nodes.insert(NodeName::Due, NodeValue::Text(next.timestamp().to_string()));
+            self.modified = true;
+            true
+        }
+
+        /// Returns whether the entry blocks any time between from and to
+        pub fn occupies(&self, from: DateTime<Local>, to: DateTime<Local>) -> bool {
+            !self.occurrences(from, to).is_empty()
+        }
+
+        /// Returns the first occurrence of the entry taking place on the day
+        pub fn occurrence_on_day(&self, day: NaiveDate) -> Option<(DateTime<Local>, DateTime<Local>)> {
+            let (from, to) = data_helpers::local_day(day)?;
+            self.occurrences(from, to).into_iter().next()
+        }
+
+        /// Returns for each of the entries whether it overlaps with another one
+        /// on the day
+        pub fn overlapping(entries: &[&EntryNode], day: NaiveDate) -> Vec<bool> {
+            let occurrences: Vec<Vec<(DateTime<Local>, DateTime<Local>)>> = match data_helpers::local_day(day) {
+                Some((from, to)) => entries.iter().map(|e| e.occurrences(from, to)).collect(),
+                None => vec![Vec::new(); entries.len()],
+            };
+            let overlap = |(a_start, a_end): &(DateTime<Local>, DateTime<Local>), (b_start, b_end): &(DateTime<Local>, DateTime<Local>)| {
+                *a_start < *b_start + Self::blocked(*b_start, *b_end) && *b_start < *a_start + Self::blocked(*a_start, *a_end)
+            };
+            occurrences
+                .iter()
+                .enumerate()
+                .map(|(i, a)| {
+                    occurrences
+                        .iter()
+                        .enumerate()
+                        .any(|(j, b)| i != j && a.iter().any(|x| b.iter().any(|y| overlap(x, y))))
+                })
+                .collect()
+        }
+
+        /// Returns whether the entry takes place on the day, entries with a
+        /// duration take place on every day they span
+        pub fn is_on_day(&self, day: NaiveDate) -> bool {
+            self.occurrence_on_day(day).is_some()
+        }
+
+        /// Returns all nodes of the element
+        pub fn nodes(&mut self) -> &mut HashMap<NodeName, NodeValue> {
+            &mut self.nodes
+        }
+
+        /// Returns the nodes of the element as an Iter
+        pub fn get_nodes(&self) -> Iter<'_, NodeName, NodeValue> {
+            self.nodes.iter()
+        }
+
+        /// Sets this element to modified
+        pub fn modified(&mut self) {
+            self.modified = true;
+        }
+
+        /// A function that returns the App Element in a Form of Vectors
+        /// where each element is another vector consisting of the key
+        /// and the value. Also sorts the elements
+        pub fn get_vecs(&self) -> Vec<(String, String)> {
+            data_helpers::flatten_sorted(&self.nodes)
+        }
+
+        /// A function that returns the title followed by the description
+        /// followed by all other attributes as a single lowercase string, this
+        /// is designed for usage of searching and filtering
+        pub fn get_text(&self) -> String {
+            let mut text: Vec<String> = Vec::with_capacity(self.node_count());
+            text.extend(self.title());
+            text.extend(self.description());
+            text.extend(self
+                .get_nodes()
+                .filter(|(name, _)| **name != NodeName::Title && **name != NodeName::Description)
+                .map(|(_, value)| value.to_string())
+            );
+            text.join(" ").to_lowercase()
+        }
+
+        /// Generates a new ID for this element. The id will not be in existing ids
+        /// Updates the self element and the existing ids
+        /// Returns the new id
+        pub fn generate_id(&mut self, existing_ids: &mut Vec<u16>) -> u16 {
+            let new_id: u16 = data_helpers::generate_id(existing_ids);
+            self.id = Some(new_id);
+            new_id
+        }
+
+        /// Returns the AppElement as an ListItem indented by the given depth
+        pub fn to_list_item<'a>(&self, depth: usize) -> ListItem<'a> {
+            let mut item = ListItem::new(format!(
+                "{}{}{}{}",
+                crate::DISPLAY_INDENT_STRING.repeat(depth),
+                if self.is_done() { "[x] " } else { "" },
+                if self.recurrence().is_some() { "~ " } else { "" },
+                self.title().unwrap_or("<no title>".to_string())
+            ));
+            if self.removed {
+                item = item.style(Style::default().add_modifier(Modifier::CROSSED_OUT));
+            } else if self.is_done() {
+                item = item.style(Style::default().add_modifier(Modifier::DIM | Modifier::ITALIC));
+            } else if self.modified {
+                item = item.style(Style::default().add_modifier(Modifier::BOLD));
+            } else if self.id.is_none() {
+                item = item.style(Style::default().add_modifier(Modifier::ITALIC));
+            }
+            item
+        }
+    }
+
+    /// An attribute or the directory of a node that was changed locally and
+    /// on the server to different values since the last sync
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Conflict {
+        pub id: u16,
+        pub title: String,
+        pub attribute: String,
+        pub base: Option<String>,
+        pub local: Option<String>,
+        pub remote: Option<String>,
+        /// The directories the node is located in if it was moved to different
+        /// directories, None for conflicting attributes
+        pub parents: Option<ConflictParents>,
+    }
+
+    /// The IDs of the directories a node is located in on each side of a
+    /// conflict, None standing for the top level
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub struct ConflictParents {
+        pub base: Option<u16>,
+        pub local: Option<u16>,
+        pub remote: Option<u16>,
+    }
+
+    /// Which value should be kept when resolving a conflict
+    pub enum ConflictChoice {
+        Local,
+        Remote,
+        Base,
+    }
 
-    #[derive(Debug, Clone)]
-    struct DirectoryNode {
-        id: Option<u16>,
-        nodes: HashMap<NodeName, NodeValue>,
-        content: Vec<RegistryNode>,
+    /// How a query term compares the value of an attribute
+    #[derive(Debug, Clone, Copy, PartialEq)]
+    pub enum QueryOperator {
+        /// ':' the value contains the text, ignoring the case
+        Contains,
+        /// '=' the value is the text, ignoring the case
+        Equals,
+        /// '<'
+        Less,
+        /// '<='
+        LessEqual,
+        /// '>'
+        Greater,
+        /// '>='
+        GreaterEqual,
     }
 
-    impl DirectoryNode {
-        fn new(id: Option<u16>, nodes: HashMap<NodeName, NodeValue>, content: Vec<RegistryNode>) -> Self {
-            Self {
-                id,
-                nodes,
-                content,
+    impl QueryOperator {
+        /// All operators with the longest symbols first, so they are found
+        /// before their prefixes
+        const ALL: [(&'static str, QueryOperator); 6] = [
+            ("<=", Self::LessEqual),
+            (">=", Self::GreaterEqual),
+            ("<", Self::Less),
+            (">", Self::Greater),
+            ("=", Self::Equals),
+            (":", Self::Contains),
+        ];
+
+        /// Returns whether the ordering of a value compared to the queried
+        /// one satisfies the operator
+        fn accepts(&self, ordering: std::cmp::Ordering) -> bool {
+            match self {
+                Self::Contains | Self::Equals => ordering.is_eq(),
+                Self::Less => ordering.is_lt(),
+                Self::LessEqual => ordering.is_le(),
+                Self::Greater => ordering.is_gt(),
+                Self::GreaterEqual => ordering.is_ge(),
             }
         }
     }
 
-    /// The Keynames for different Nodes
-    #[derive(PartialEq, Eq, Hash, Debug, Clone)]
-    pub enum NodeName {
-        /// The title
-        Title,
-        /// A description
-        Description,
-        /// The Location aka. where it takes place
-        Location,
-        /// A color which can optionally be used for display purposes
-        Color,
-        /// A due date
-        Due,
-        /// How long it takes place in minutes
-        Duration,
-        /// How to trigger an alert
-        Alert,
-        /// Any other elements
-        Other(String),
+    /// A single condition of a query
+    #[derive(Debug, Clone, PartialEq)]
+    enum QueryCondition {
+        /// Any attribute contains the text
+        Text(String),
+        /// The attribute exists
+        Has(Vec<NodeName>),
+        /// The attribute compares to the value
+        Compare(Vec<NodeName>, QueryOperator, String),
     }
 
-    impl fmt::Display for NodeName {
+    /// A parsed query like `due<tomorrow location:office -color=red has:alert`
+    /// Every term has to match, terms starting with '-' must not match
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct Query {
+        text: String,
+        terms: Vec<(bool, QueryCondition)>,
+    }
+
+    impl fmt::Display for Query {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.text)
+        }
+    }
+
+    impl Query {
+        /// Parses the query, fails with a message describing the invalid term
+        pub fn parse(text: &str) -> Result<Self, String> {
+            let terms: Vec<(bool, QueryCondition)> = data_helpers::split_query(text)?
+                .iter()
+                .map(|term| {
+                    let (negated, term): (bool, &str) = match term.strip_prefix('-') {
+                        Some(t) if !t.is_empty() => (true, t),
+                        _ => (false, term.as_str()),
+                    };
+                    Ok((negated, Self::parse_condition(term)?))
+                })
+                .collect::<Result<Vec<_>, String>>()?;
+            Ok(Self { text: text.trim().to_string(), terms })
+        }
+
+        /// Turns the attribute path of a term into the chain of node names,
+        /// accepting 'title' for the name
+        fn parse_path(path: &str) -> Vec<NodeName> {
+            path
+                .split(crate::DISPLAY_NESTING_STRING)
+                .map(|n| match n.to_lowercase().as_str() {
+                    "title" => NodeName::Title,
+                    n => NodeName::from_str(n),
+                })
+                .collect()
+        }
+
+        fn parse_condition(term: &str) -> Result<QueryCondition, String> {
+            let found: Option<(usize, &str, QueryOperator)> = QueryOperator::ALL
+                .iter()
+                .filter_map(|(symbol, op)| Some((term.find(symbol)?, *symbol, *op)))
+                .min_by_key(|(indx, symbol, _)| (*indx, usize::MAX - symbol.len()));
+
+            let (indx, symbol, op) = match found {
+                Some(f) => f,
+                None => return Ok(QueryCondition::Text(term.to_lowercase())),
+            };
+            let (name, value) = (&term[..indx], &term[indx + symbol.len()..]);
+            if name.is_empty() {
+                return Err(format!("Missing attribute in '{}'", term));
+            }
+            if value.is_empty() {
+                return Err(format!("Missing value in '{}'", term));
+            }
+            if op == QueryOperator::Contains && name.eq_ignore_ascii_case("has") {
+                return Ok(QueryCondition::Has(Self::parse_path(value)));
+            }
+
+            let path: Vec<NodeName> = Self::parse_path(name);
+            if path.last() == Some(&NodeName::Due) && op != QueryOperator::Contains && data_helpers::parse_time(value).is_none() {
+                return Err(format!("Cannot parse time '{}'", value));
+            }
+            Ok(QueryCondition::Compare(path, op, value.to_string()))
+        }
+
+        /// Returns whether the query has no terms and matches everything
+        pub fn is_empty(&self) -> bool {
+            self.terms.is_empty()
+        }
+
+        /// Returns the texts that are searched for in any or a single
+        /// attribute, used to highlight them
+        pub fn highlights(&self) -> Vec<&str> {
+            self.terms
+                .iter()
+                .filter(|(negated, _)| !negated)
+                .filter_map(|(_, c)| match c {
+                    QueryCondition::Text(t) => Some(t.as_str()),
+                    QueryCondition::Compare(_, QueryOperator::Contains, v) => Some(v.as_str()),
+                    _ => None,
+                })
+                .collect()
+        }
+
+        /// Returns whether the node matches all terms of the query
+        pub fn matches(&self, node: &RegistryNode) -> bool {
+            self.terms
+                .iter()
+                .all(|(negated, c)| *negated != Self::matches_condition(c, node))
+        }
+
+        fn matches_condition(condition: &QueryCondition, node: &RegistryNode) -> bool {
+            let (path, op, value) = match condition {
+                QueryCondition::Text(t) => return node.matches(t),
+                QueryCondition::Has(path) => return data_helpers::get_chain_from_map(node.attributes(), path).is_some(),
+                QueryCondition::Compare(path, op, value) => (path, op, value),
+            };
+            let actual: String = match data_helpers::get_chain_from_map(node.attributes(), path) {
+                Some(v) => v.to_string(),
+                None => return false,
+            };
+
+            if *op == QueryOperator::Contains {
+                return actual.to_lowercase().contains(&value.to_lowercase());
+            }
+            let ordering: Option<std::cmp::Ordering> = if path.last() == Some(&NodeName::Due) {
+                match (actual.trim().parse::<i64>().ok(), data_helpers::parse_time(value)) {
+                    (Some(a), Some(v)) => Some(a.cmp(&v)),
+                    _ => None,
+                }
+            } else {
+                Some(data_helpers::compare_values(&actual, value))
+            };
+            ordering.is_some_and(|o| op.accepts(o))
+        }
+    }
+
+    /// What the listed nodes are sorted by
+    #[derive(Debug, Clone, PartialEq)]
+    pub enum SortKey {
+        /// The order of the nodes in the registry
+        Manual,
+        /// The ID, which grows with the creation of the nodes
+        Id,
+        /// The value of any attribute
+        Attribute(Vec<NodeName>),
+    }
+
+    impl fmt::Display for SortKey {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Manual => write!(f, "manual"),
+                Self::Id => write!(f, "id"),
+                Self::Attribute(path) => write!(
+                    f,
+                    "{}",
+                    path.iter().map(|n| n.to_string()).collect::<Vec<String>>().join(crate::DISPLAY_NESTING_STRING)
+                ),
+            }
+        }
+    }
+
+    impl SortKey {
+        /// The keys that can be cycled through
+        pub fn presets() -> Vec<Self> {
+            vec![
+                Self::Manual,
+                Self::Attribute(vec![NodeName::Due]),
+                Self::Attribute(vec![NodeName::Title]),
+                Self::Id,
+                Self::Attribute(vec![NodeName::Duration]),
+                Self::Attribute(vec![NodeName::Location]),
+            ]
+        }
+    }
+
+    /// The order nodes are listed in
+    #[derive(Debug, Clone, PartialEq)]
+    pub struct SortOrder {
+        pub key: SortKey,
+        pub descending: bool,
+    }
+
+    impl fmt::Display for SortOrder {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self.descending {
+                true => write!(f, "-{}", self.key),
+                false => write!(f, "{}", self.key),
+            }
+        }
+    }
+
+    impl SortOrder {
+        pub fn manual() -> Self {
+            Self { key: SortKey::Manual, descending: false }
+        }
+
+        /// Parses 'manual', 'id' or the path of the attribute to sort by,
+        /// sorting descending if prefixed with '-'. Returns None if empty
+        pub fn parse(text: &str) -> Option<Self> {
+            let text: &str = text.trim();
+            let (descending, name): (bool, &str) = match text.strip_prefix('-') {
+                Some(n) => (true, n),
+                None => (false, text),
+            };
+            let key: SortKey = match name.to_lowercase().as_str() {
+                "" => return None,
+                "manual" => SortKey::Manual,
+                "id" => SortKey::Id,
+                "title" => SortKey::Attribute(vec![NodeName::Title]),
+                _ => SortKey::Attribute(data_helpers::name_chain(name)),
+            };
+            // The registry can only be listed in its own order
+            let descending: bool = descending && key != SortKey::Manual;
+            Some(Self { key, descending })
+        }
+
+        /// Returns whether the nodes are listed in the order of the registry
+        pub fn is_manual(&self) -> bool {
+            self.key == SortKey::Manual
+        }
+
+        /// Compares two nodes by the key, nodes without a value come last
+        pub fn compare(&self, a: &RegistryNode, b: &RegistryNode) -> std::cmp::Ordering {
+            let value = |n: &RegistryNode| -> Option<String> {
+                match &self.key {
+                    SortKey::Manual => None,
+                    SortKey::Id => Some(n.id()?.to_string()),
+                    SortKey::Attribute(path) => Some(data_helpers::get_chain_from_map(n.attributes(), path)?.to_string()),
+                }
+            };
+            match (value(a), value(b)) {
+                (Some(a), Some(b)) if self.descending => data_helpers::compare_values(&b, &a),
+                (Some(a), Some(b)) => data_helpers::compare_values(&a, &b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => std::cmp::Ordering::Equal,
+            }
+        }
+    }
+
+    /// Everything needed to talk to the configured server, cheap to clone
+    /// into tasks running in the background
+    #[derive(Clone)]
+    pub struct Connection {
+        client: Client,
+        server: ServerProfile,
+        secret: String,
+    }
+
+    impl Connection {
+        /// Makes a call to the configured server using the provided endpoint
+        async fn call(&self, endpoint: &str, payload: String) -> Result<Response, AppError> {
+            let user: HeaderValue = HeaderValue::from_str(&self.server.username)
+                .map_err(|_| AppError::Config("The username contains invalid characters".to_string()))?;
+            let res: Response = self.client
+                .post(format!("{}{}", self.server.server_address, endpoint))
+                .header(
+                    "user".to_string(),
+                    user
+                )
+                .header(
+                    format!("{}", &self.server.auth_method).to_lowercase(),
+                    &self.secret
+                )
+                .header(
+                    "content-type".to_string(),
+                    "text/xml".to_string(),
+                )
+                .body(payload)
+                .send()
+                .await?;
+
+            if let Some(e) = AppError::from_status(res.status().as_u16()) {
+                return Err(e);
+            }
+
+            Ok(res)
+        }
+
+        /// Fetches the whole registry from the server
+        pub async fn fetch(&self) -> Result<String, AppError> {
+            let res: Response = self.call("/xml/fetch", "".to_string()).await?;
+
+            // Parameters like the charset are not of interest
+            let content_type: String = res
+                .headers()
+                .get("content-type")
+                .and_then(|h| h.to_str().ok())
+                .and_then(|h| h.split(';').next())
+                .unwrap_or("")
+                .trim()
+                .to_lowercase();
+            if !matches!(content_type.as_str(), "text/xml" | "application/xml") {
+                return Err(AppError::ContentType(content_type));
+            }
+
+            let txt: String = res.text().await?;
+            if txt.trim().is_empty() {
+                return Err(AppError::ContentType("empty response".to_string()));
+            }
+
+            Ok(txt)
+        }
+
+        /// Uploads the given payload to the server and returns the HTTP status code
+        pub async fn upload(&self, payload: String) -> Result<u16, AppError> {
+            let res: Response = self.call("/xml/update", payload).await?;
+
+            let status: u16 = res.status().as_u16();
+
+            Ok(status)
+        }
+    }
+
+    /// The phases a running sync goes through
+    #[derive(PartialEq)]
+    pub enum SyncPhase {
+        Fetching,
+        Merging,
+        Uploading,
+    }
+
+    impl fmt::Display for SyncPhase {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             write!(f, "{}", match self {
-                Self::Title => "name",
-                Self::Description => "description",
-                Self::Location => "location",
-                Self::Color => "color",
-                Self::Due => "due",
-                Self::Duration => "duration",
-                Self::Alert => "alert",
-                Self::Other(val) => val,
+                Self::Fetching  => "fetching",
+                Self::Merging   => "merging",
+                Self::Uploading => "uploading",
             })
         }
     }
 
-    impl NodeName {
-        pub fn from_str(s: &str) -> Self {
-            match s.to_lowercase().as_str() {
-                "name" => Self::Title,
-                "description" => Self::Description,
-                "location" => Self::Location,
-                "color" => Self::Color,
-                "due" => Self::Due,
-                "duration" => Self::Duration,
-                "alert" => Self::Alert,
-                e => Self::Other(e.to_string())
+    /// The result of a sync phase running in the background
+    pub enum SyncEvent {
+        Fetched(Result<String, AppError>),
+        Uploaded(Result<u16, AppError>),
+    }
+
+    /// Everything that can go wrong while talking to the server or handling
+    /// its answers and the configuration
+    #[derive(Debug)]
+    pub enum AppError {
+        /// The server could not be reached or the connection broke
+        Network(reqwest::Error),
+        /// The server answered with an unexpected HTTP status code
+        Status(u16),
+        /// The server rejected the credentials
+        Authentication(u16),
+        /// The server answered with something that is not a registry
+        ContentType(String),
+        /// A document could not be parsed or written
+        Xml(quick_xml::Error),
+        /// The configuration is invalid or could not be read or saved
+        Config(String),
+        /// The secret could not be obtained from its source
+        Secret(String),
+    }
+
+    impl fmt::Display for AppError {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            match self {
+                Self::Network(e) => write!(f, "Network error: {}", e),
+                Self::Status(code) => write!(f, "Server responded with HTTP {}", code),
+                Self::Authentication(code) => write!(f, "Authentication failed (HTTP {}), check your credentials", code),
+                Self::ContentType(t) => write!(f, "Server responded with unexpected content: {}", t),
+                Self::Xml(e) => write!(f, "Invalid XML: {}", e),
+                Self::Config(e) => write!(f, "Configuration error: {}", e),
+                Self::Secret(e) => write!(f, "Could not obtain the secret: {}", e),
             }
         }
+    }
 
-        pub fn order(&self) -> u8 {
+    impl From<reqwest::Error> for AppError {
+        fn from(e: reqwest::Error) -> Self {
+            Self::Network(e)
+        }
+    }
+
+    impl From<quick_xml::Error> for AppError {
+        fn from(e: quick_xml::Error) -> Self {
+            Self::Xml(e)
+        }
+    }
+
+    impl AppError {
+        /// Returns the error for an unsuccessful HTTP status code if it is one
+        pub fn from_status(status: u16) -> Option<Self> {
+            match status {
+                200..=299 => None,
+                401 | 403 => Some(Self::Authentication(status)),
+                _ => Some(Self::Status(status)),
+            }
+        }
+    }
+
+    /// On which column the current acting focus is
+    #[derive(Debug, PartialEq, Eq)]
+    pub enum AppFocus {
+        Elements,
+        Attributes,
+        Edit,
+        Conflicts,
+        Search,
+        Calendar,
+    }
+
+    /// How an alert is triggered, as given in the alert attribute
+    #[derive(Debug, Clone)]
+    pub enum AlertRule {
+        /// The number of minutes before the entry is due, may be suffixed
+        /// with 'm', 'h' or 'd'
+        Before(i64),
+        /// Whenever the cron expression matches
+        Schedule(Box<Schedule>),
+    }
+
+    impl AlertRule {
+        /// Parses an offset before the due date or a cron expression
+        pub fn parse(value: &str) -> Option<Self> {
+            let value: &str = value.trim();
+            let offset: Option<i64> = match value.char_indices().last() {
+                Some((i, 'm')) => value[..i].trim().parse::<i64>().ok(),
+                Some((i, 'h')) => value[..i].trim().parse::<i64>().ok().map(|h| h * 60),
+                Some((i, 'd')) => value[..i].trim().parse::<i64>().ok().map(|d| d * 60 * 24),
+                _ => value.parse::<i64>().ok(),
+            };
+            match offset {
+                Some(minutes) => Some(Self::Before(minutes)),
+                None => Schedule::from_str(value).ok().map(|s| Self::Schedule(Box::new(s))),
+            }
+        }
+
+        /// Returns when the alert last fired if it is still pending. Alerts
+        /// before the due date stay pending until the entry has ended,
+        /// any alert stays pending for at least the window
+        pub fn last_fired(&self, entry: &EntryNode, now: DateTime<Local>, window: chrono::Duration) -> Option<DateTime<Local>> {
             match self {
-                Self::Title => 0,
-                Self::Description => 1,
-                Self::Location => 2,
-                Self::Due => 3,
-                Self::Duration => 4,
-                Self::Other(v) => 127 + (v.len() % 128).try_into().unwrap_or(0),
-                Self::Color => 254,
-                Self::Alert => 254,
-                _ => u8::MAX,
+                Self::Before(minutes) => {
+                    let (start, end) = entry.upcoming(now)?;
+                    let at: DateTime<Local> = start - chrono::Duration::minutes(*minutes);
+                    (at <= now && now < end.max(at + window)).then_some(at)
+                },
+                Self::Schedule(schedule) => schedule
+                    .after(&(now - window))
+                    .take_while(|at| at <= &now)
+                    .last(),
             }
         }
     }
 
-    trait Node {
-        // Writes the element using the given quick xml writer
-        /// skips silently if the element does not have an ID
-        /// Skips the outer 'entry' tags if 'with_head' is false
-        fn write<W: std::io::Write>(&self, writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error>;
+    /// What toggling the completion of an entry did
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum Completion {
+        Done,
+        Undone,
+        /// The recurring entry moved on to its next occurrence
+        Advanced,
     }
 
-    /// The Value of a subnode inside an entry node
-    /// aka. the text in between a subnode of an entry node
+    /// How an entry repeats, as given in the repeat attribute
+    #[derive(Debug, Clone)]
+    pub enum Recurrence {
+        Daily,
+        Weekly,
+        Monthly,
+        Yearly,
+        /// Whenever the cron expression matches
+        Schedule(Box<Schedule>),
+    }
+
+    impl Recurrence {
+        /// Parses a simple rule or a cron expression
+        pub fn parse(value: &str) -> Option<Self> {
+            match value.trim().to_lowercase().as_str() {
+                "daily" => Some(Self::Daily),
+                "weekly" => Some(Self::Weekly),
+                "monthly" => Some(Self::Monthly),
+                "yearly" => Some(Self::Yearly),
+                _ => Schedule::from_str(value.trim()).ok().map(|s| Self::Schedule(Box::new(s))),
+            }
+        }
+
+        /// Returns the start of the first occurrence at or after the given
+        /// time, the series starts with the first occurrence
+        pub fn next_start(&self, first: DateTime<Local>, after: DateTime<Local>) -> Option<DateTime<Local>> {
+            if after <= first {
+                return Some(first);
+            }
+            let (days, months): (u64, u32) = match self {
+                Self::Schedule(schedule) => return schedule.after(&(after - chrono::Duration::seconds(1))).next(),
+                Self::Daily => (1, 0),
+                Self::Weekly => (7, 0),
+                Self::Monthly => (0, 1),
+                Self::Yearly => (0, 12),
+            };
+            // Start shortly before the result and step forward from there
+            let mut step: u64 = match days {
+                0 => {
+                    let months_between: i64 = (after.year() - first.year()) as i64 * 12 + after.month() as i64 - first.month() as i64;
+                    (months_between.max(1) as u64 - 1) / months as u64
+                },
+                _ => (after - first).num_days().max(0) as u64 / days,
+            };
+            loop {
+                let next: DateTime<Local> = match days {
+                    0 => first.checked_add_months(Months::new(step as u32 * months))?,
+                    _ => first.checked_add_days(Days::new(step * days))?,
+                };
+                if next >= after {
+                    return Some(next);
+                }
+                step += 1;
+            }
+        }
+    }
+
+    /// An alert that fired for an entry
     #[derive(Debug, Clone, PartialEq)]
-    pub enum NodeValue {
-        Text(String),
-        NestedNode(HashMap<NodeName, NodeValue>),
+    pub struct Alert {
+        /// The ID of the entry
+        pub id: u16,
+        /// When the alert fired as timestamp
+        pub at: i64,
+        pub title: String,
+        pub due: Option<u32>,
+        pub location: Option<String>,
+    }
+
+    impl fmt::Display for Alert {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", self.title)?;
+            if let Some(due) = self.due_time() {
+                write!(f, "\nDue {}", due.format("%a, %d %b %H:%M"))?;
+            }
+            if let Some(location) = &self.location {
+                write!(f, "\nAt {}", location)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl Alert {
+        /// Returns when the alerted entry is due in local time
+        pub fn due_time(&self) -> Option<DateTime<Local>> {
+            Local.timestamp_opt(self.due? as i64, 0).earliest()
+        }
+
+        /// Returns the earliest alert of the entries that is pending and
+        /// for which ignore returns false. Entries are only alerted once
+        /// they have an ID
+        pub fn pending<F: Fn(u16, i64) -> bool>(entries: &[&EntryNode], now: DateTime<Local>, window: chrono::Duration, ignore: F) -> Option<Self> {
+            entries
+                .iter()
+                .filter(|e| !e.removed && !e.is_done())
+                .filter_map(|e| {
+                    let id: u16 = e.id?;
+                    let at: i64 = e.alert_rule()?.last_fired(e, now, window)?.timestamp();
+                    match ignore(id, at) {
+                        true => None,
+                        false => Some(Self {
+                            id,
+                            at,
+                            title: e.title().unwrap_or("<no title>".to_string()),
+                            due: e.due(),
+                            location: e.location(),
+                        }),
+                    }
+                })
+                .min_by_key(|a| a.at)
+        }
+    }
+
+    /// How the events are laid out in the main view
+    #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+    pub enum AppLayout {
+        /// A list of all events
+        List,
+        /// A month grid with the events of the selected day listed below
+        Calendar,
+        /// All events with a due date in chronological order grouped by day
+        Agenda,
+        /// A grid of the hours of the week with the events of the selected
+        /// day listed below
+        Week,
     }
 
-    /*
-    impl ToString for NodeValue {
-        fn to_string(&self) -> String {
+    impl AppLayout {
+        /// Returns the layout following this one
+        pub fn next(&self) -> Self {
             match self {
-                Self::Text(t) => {
-                    t.to_string()
-                },
-                Self::NestedNode(n) => {
-                    n.iter()
-                        .map(|e| e.name().to_string())
-                        .collect::<Vec<String>>()
-                        .join(" ")
-                }
+                Self::List => Self::Calendar,
+                Self::Calendar => Self::Agenda,
+                Self::Agenda => Self::Week,
+                Self::Week => Self::List,
             }
         }
     }
-    */
 
-    impl fmt::Display for NodeValue {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            match self {
-                NodeValue::Text(t) => {
-                    write!(f, "{}", t)
-                },
-                NodeValue::NestedNode(n) => {
-                    n
-                        .iter()
-                        .for_each(|(k, v)| {
-                            write!(f, "{} - {}\n", k, v).unwrap(); // FIXME CHANGEME RETARD
-                        });
-                    Ok(())
-                }
-            }
+    impl AppFocus {
+        /// Returns true if the current AppFocus is on Elements
+        pub fn elements(&self) -> bool {
+            self == &Self::Elements
+        }
+        /// Returns true if the current AppFocus is on Attributes
+        pub fn attributes(&self) -> bool {
+            self == &Self::Attributes
+        }
+        /// Returns true if the current AppFocus is on Edit
+        pub fn edit(&self) -> bool {
+            self == &Self::Edit
         }
     }
 
-    impl NodeValue {
-        /// Writes the Nodevalue into the provided xml writer
-        fn write_xml<W: std::io::Write>(&self, mut writer: &mut Writer<W>) -> Result<(), quick_xml::Error> {
-            match self {
-                Self::Text(t) => {
-                    writer.write_event(Event::Text(BytesText::new(t)))?;
-                },
-                Self::NestedNode(n) => {
-                    n
-                        .iter()
-                        .for_each(|(n, v)| {
-                            // Fix error handling here
-                            writer.write_event(Event::Start(BytesStart::new(n.to_string()))).unwrap_or(());
-                            v.write_xml(&mut writer).unwrap_or(());
-                            writer.write_event(Event::End(BytesEnd::new(n.to_string()))).unwrap_or(());
-                            
-                        });
-                }
-            };
+    /// A node without the content of a directory and where it is located
+    #[derive(Debug, Clone)]
+    struct Placement {
+        node: RegistryNode,
+        /// The key of the directory the node is located in, None for the top level
+        parent: Option<u64>,
+        depth: usize,
+        index: usize,
+    }
 
-            Ok(())
+    impl Placement {
+        /// Returns whether the node is the same and located at the same place,
+        /// whether a directory is expanded is not part of a change
+        fn same_as(&self, other: &Placement) -> bool {
+            self.parent == other.parent
+                && self.index == other.index
+                && self.node.id() == other.node.id()
+                && self.node.attributes() == other.node.attributes()
+                && self.node.removed() == other.node.removed()
+                && self.node.is_modified() == other.node.is_modified()
+                && self.node.is_moved() == other.node.is_moved()
         }
     }
 
+    /// A node as it was before a change, None if it did not exist
     #[derive(Debug, Clone)]
-    pub struct EntryNode {
-        id: Option<u16>,
-        nodes: HashMap<NodeName, NodeValue>,
-        removed: bool,
-        modified: bool,
+    struct NodeChange {
+        key: u64,
+        before: Option<Placement>,
     }
 
-    impl PartialEq for EntryNode {
-        fn eq(&self, other: &Self) -> bool {
-            match self.id {
-                Some(id) => Some(id) == other.id,
-                None => self == other, // Isn't this recursive???
+    /// Where every node of the local registry was before a change, to find
+    /// out which nodes the change touched once it is recorded
+    pub struct Snapshot {
+        /// What the change did, e.g. "removal of attribute 'location'"
+        label: String,
+        placements: HashMap<u64, Placement>,
+        synced: bool,
+        selected: Option<Vec<usize>>,
+    }
+
+    /// A change of the local registry that can be undone or redone. Only the
+    /// nodes the change touched are kept, identified by their key, so the
+    /// history stays small and survives syncs. Changes of nodes that were
+    /// changed on the server in the meantime are forgotten when merging
+    struct Change {
+        label: String,
+        nodes: Vec<NodeChange>,
+        /// Whether everything was synced before the change
+        synced: bool,
+        selected: Option<Vec<usize>>,
+        /// Whether the registry was merged since the change, so reverting it
+        /// has to be synced again
+        merged: bool,
+    }
+
+    /// The current state of the app
+    pub struct AppState {
+        config: AppConfig,
+        client: Option<Client>,
+        /// The secret of the profile in use once it was obtained
+        secret: Option<String>,
+        registry: Registry,
+        /// The attributes of every node as they were at the last sync
+        base: HashMap<u16, HashMap<NodeName, NodeValue>>,
+        /// The directory every node was located in at the last sync
+        base_parents: HashMap<u16, Option<u16>>,
+        conflicts: Vec<Conflict>,
+        synced: bool,
+        last_sync: Option<DateTime<Local>>,
+        sync_phase: Option<SyncPhase>,
+        sync_task: Option<JoinHandle<SyncEvent>>,
+        /// The registry as it is on the server once the running sync is finished
+        pushed: Option<Registry>,
+        pub focused_on: AppFocus,
+        pub list_state: ListState,
+        /// The state of the agenda, which lists day headings between the nodes
+        pub agenda_state: ListState,
+        pub details_state: TableState,
+        pub conflict_state: TableState,
+        pub prompt: Option<String>,
+        pub message: Option<String>,
+        modification_buffer: Option<String>,
+        /// The query the listed nodes are filtered by as typed
+        search: Option<String>,
+        /// The last valid query parsed from the search
+        query: Option<Query>,
+        /// Why the search could not be parsed into a query
+        query_error: Option<String>,
+        /// The index of the saved view in use
+        view: Option<usize>,
+        /// The order the listed nodes are sorted in
+        sort: SortOrder,
+        layout: AppLayout,
+        /// The day selected in the calendar
+        calendar_day: NaiveDate,
+        /// The hour selected in the week view
+        calendar_hour: u32,
+        /// The alert currently shown
+        alert: Option<Alert>,
+        /// The entry IDs and times of all alerts that were dismissed
+        dismissed: BTreeSet<(u16, i64)>,
+        /// When the snoozed alerts should fire again
+        snoozed: HashMap<(u16, i64), DateTime<Local>>,
+        /// The changes that can be undone, the latest last
+        undo_history: VecDeque<Change>,
+        /// The changes that were undone and can be redone, the latest last
+        redo_history: Vec<Change>,
+    }
+
+    impl AppState {
+        pub fn new(config: AppConfig) -> Self {
+            let sort: SortOrder = config.sort_order();
+            Self {
+                config,
+                client: None,
+                secret: None,
+                registry: Registry::empty(),
+                base: HashMap::new(),
+                base_parents: HashMap::new(),
+                conflicts: Vec::new(),
+                synced: false,
+                last_sync: None,
+                sync_phase: None,
+                sync_task: None,
+                pushed: None,
+                focused_on: AppFocus::Elements,
+                list_state: ListState::default(),
+                agenda_state: ListState::default(),
+                details_state: TableState::default(),
+                conflict_state: TableState::default(),
+                prompt: None,
+                message: None,
+                modification_buffer: None,
+                search: None,
+                query: None,
+                query_error: None,
+                view: None,
+                sort,
+                layout: AppLayout::List,
+                calendar_day: Local::now().date_naive(),
+                calendar_hour: Local::now().hour(),
+                alert: None,
+                dismissed: BTreeSet::new(),
+                snoozed: HashMap::new(),
+                undo_history: VecDeque::new(),
+                redo_history: Vec::new(),
             }
         }
-    }
 
-    impl AsRef<Self> for EntryNode {
-        fn as_ref(&self) -> &Self {
-            return &self;
+        /// Checks if we are currently Editing by checking whether the modification
+        /// buffer is some
+        pub fn is_editing(&self) -> bool {
+            self.modification_buffer.is_some()
         }
-    }
 
-    impl fmt::Display for EntryNode {
-        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-            /*
-            let disp_due: String = match self.nodes.get("due") {
-                Some(due) => {
-                    let due_timestamp: i64 = due.parse().unwrap_or(-1);
-                    let utc_due: String = match Utc.timestamp_opt(due_timestamp, 0) {
-                        LocalResult::None => "None".to_string(),
-                        LocalResult::Single(val) => val.with_timezone(&chrono::Local).to_rfc2822(),
-                        LocalResult::Ambiguous(val, _) => val.with_timezone(&chrono::Local).to_rfc2822(),
-                    };
-                    utc_due
+        /// Returns the paths of all currently visible nodes, only the matching
+        /// ones and their directories while filtering
+        fn visible_paths(&self) -> Vec<Vec<usize>> {
+            let mut paths: Vec<Vec<usize>> = match (self.get_query(), self.layout) {
+                (query, AppLayout::Calendar | AppLayout::Week) => self.registry.matching_paths(&|n: &RegistryNode| {
+                    matches!(n, RegistryNode::Element(e) if e.is_on_day(self.calendar_day))
+                        && query.is_none_or(|q| q.matches(n))
+                }),
+                (_, AppLayout::Agenda) => {
+                    return self.agenda_rows()
+                        .into_iter()
+                        .map(|(path, _, _)| path)
+                        .collect();
                 },
-                None => "None".to_string()
-            };
-            */
-        
-            let id: String = match self.id {
-                Some(id) => format!("{}", id),
-                None => "None".to_string()
+                (Some(query), _) => self.registry.matching_paths(&|n: &RegistryNode| query.matches(n)),
+                (None, _) => self.registry.visible_paths(),
             };
-            write!(
-                f,
-                "ID: {}",
-                id,
-            )
+            paths.retain(|p| !self.registry.node(p).is_some_and(|n| self.hides(n)));
+            if !self.sort.is_manual() {
+                self.registry.sort_paths(&mut paths, &|a, b| self.sort.compare(a, b));
+            }
+            paths
         }
-    }
 
-    impl Node for EntryNode {
-        /// Writes the element using the given quick xml writer
-        /// skips silently if the element does not have an ID
-        /// Skips the outer 'entry' tags if 'with_head' is false
-        fn write<W: std::io::Write>(&self, mut writer: &mut Writer<W>, with_head: bool) -> Result<(), quick_xml::Error> {
-            if self.id.is_none() {
-                return Ok(());
-            }
-            if with_head {
-                writer.write_event(Event::Start(
-                    BytesStart::new("entry")
-                        .with_attributes([Attribute::from(("id", self.id.unwrap().to_string().as_str()))])
-                    )
-                )?;
-            }
+        /// Returns all currently visible nodes alongside their nesting depth,
+        /// in the order they are displayed in
+        pub fn get_visible_nodes(&self) -> Vec<(usize, &RegistryNode)> {
+            self.visible_paths()
+                .iter()
+                .filter_map(|p| Some((p.len() - 1, self.registry.node(p)?)))
+                .collect()
+        }
 
-            for (key, value) in self.nodes.iter() {
-                writer.write_event(Event::Start(BytesStart::new(key.to_string())))?;
-                value.write_xml(&mut writer)?;
-                writer.write_event(Event::End(BytesEnd::new(key.to_string())))?;
-            }
+        /// Returns whether the node is left out of the lists because it was
+        /// completed
+        fn hides(&self, node: &RegistryNode) -> bool {
+            self.config.hide_done && matches!(node, RegistryNode::Element(e) if e.is_done())
+        }
 
-            if with_head {
-                writer.write_event(Event::End(BytesEnd::new("entry")))?;
+        /// Shows or hides completed entries, returns whether they are hidden now
+        pub fn toggle_hide_done(&mut self) -> bool {
+            self.config.hide_done = !self.config.hide_done;
+            self.list_state.select(None);
+            self.config.hide_done
+        }
+
+        /// Returns the number of synced entries that were not removed
+        pub fn entry_count(&self) -> usize {
+            self.get_ids(true).len()
+        }
+
+        /// Returns the path of the currently selected node if available
+        pub fn get_selected_path(&self) -> Option<Vec<usize>> {
+            self.visible_paths()
+                .into_iter()
+                .nth(self.list_state.selected()?)
+        }
+
+        /// Selects the node found at the given path if it is visible
+        pub fn select_path(&mut self, path: &[usize]) {
+            if let Some(indx) = self.visible_paths().iter().position(|p| p == path) {
+                self.list_state.select(Some(indx));
             }
+        }
 
-            Ok(())
+        /// Returns the current search text if the nodes are filtered by one
+        pub fn get_search(&self) -> Option<&str> {
+            self.search.as_deref().filter(|s| !s.is_empty())
         }
-    }
 
-    impl EntryNode {
-        pub fn new(id: Option<u16>, nodes: HashMap<NodeName, NodeValue>) -> Self {
-            Self {
-                id,
-                nodes,
-                removed: false,
-                modified: false,
+        /// Returns the query the nodes are filtered by if it has any terms
+        pub fn get_query(&self) -> Option<&Query> {
+            self.query.as_ref().filter(|q| !q.is_empty())
+        }
+
+        /// Returns why the current search is not a valid query
+        pub fn get_query_error(&self) -> Option<&str> {
+            self.query_error.as_deref()
+        }
+
+        /// Parses the search into the query, keeping the last valid query
+        /// while the search is invalid
+        fn update_query(&mut self) {
+            match Query::parse(self.search.as_deref().unwrap_or("")) {
+                Ok(query) => {
+                    self.query = Some(query);
+                    self.query_error = None;
+                },
+                Err(e) => self.query_error = Some(e),
             }
         }
 
-        pub fn title(&self) -> Option<String> {
-            Some(self.nodes.get(&NodeName::Title)?.to_string())
+        /// Starts a new search, continuing with the current search text
+        pub fn start_search(&mut self) {
+            if self.search.is_none() {
+                self.search = Some(String::new());
+            }
+            self.focused_on = AppFocus::Search;
         }
 
-        pub fn description(&self) -> Option<String> {
-            Some(self.nodes.get(&NodeName::Description)?.to_string())
+        /// Adds the char to the search text and selects the first match
+        pub fn push_search(&mut self, c: char) {
+            self.search.get_or_insert_with(String::new).push(c);
+            self.update_query();
+            self.select_first_match();
         }
 
-        pub fn due(&self) -> Option<u32> {
-            self.nodes.get(&NodeName::Due)?
-                .to_string()
-                .parse::<u32>()
-                .ok()
+        /// Removes the last char from the search text and selects the first match
+        pub fn pop_search(&mut self) {
+            if let Some(search) = self.search.as_mut() {
+                search.pop();
+            }
+            self.update_query();
+            self.select_first_match();
         }
 
-        /// Returns the number of nodes held by the EntryNode
-        pub fn node_count(&self) -> usize {
-            self.nodes.len()
+        /// Returns how the events are laid out
+        pub fn get_layout(&self) -> AppLayout {
+            self.layout
         }
 
-        /// Returns the number of nodes and subnodes held by the EntryNode
-        pub fn flattened_node_count(&self) -> usize {
-            let node_vec = self.nodes
+        /// Switches to the next layout and focuses its main part
+        pub fn cycle_layout(&mut self) {
+            self.layout = self.layout.next();
+            self.list_state.select(None);
+            self.focused_on = match self.layout {
+                AppLayout::List | AppLayout::Agenda => AppFocus::Elements,
+                AppLayout::Calendar | AppLayout::Week => AppFocus::Calendar,
+            };
+            if self.layout == AppLayout::Agenda {
+                self.select_upcoming();
+            }
+        }
+
+        /// Selects the first node in the agenda which has not ended yet
+        pub fn select_upcoming(&mut self) {
+            let now: DateTime<Local> = Local::now();
+            let rows: Vec<(Vec<usize>, DateTime<Local>, DateTime<Local>)> = self.agenda_rows();
+            let upcoming: Option<usize> = rows
                 .iter()
-                .collect();
-            return Self::flatten_tree(&mut Vec::new(), &node_vec).len();
+                .position(|(_, _, end)| *end >= now);
+            let selected: Option<usize> = upcoming.or(rows.len().checked_sub(1));
+            self.list_state.select(selected);
         }
 
-        /// Returns all nodes of the element
-        pub fn nodes(&mut self) -> &mut HashMap<NodeName, NodeValue> {
-            &mut self.nodes
+        /// Returns the day selected in the calendar
+        pub fn get_calendar_day(&self) -> NaiveDate {
+            self.calendar_day
         }
 
-        /// Returns the nodes of the element as an Iter
-        pub fn get_nodes(&self) -> Iter<NodeName, NodeValue> {
-            return self.nodes.iter()
+        /// Moves the day selected in the calendar by the number of days
+        pub fn move_calendar_day(&mut self, days: i64) {
+            self.calendar_day += chrono::Duration::days(days);
+            self.list_state.select(None);
         }
 
-        /// Sets this element to modified
-        pub fn modified(&mut self) {
-            self.modified = true;
+        /// Returns the hour selected in the week view
+        pub fn get_calendar_hour(&self) -> u32 {
+            self.calendar_hour
         }
 
-        /// Returns the nested tree as an Vec over each nested level
-        fn flatten_tree<'a>(name_stack: &mut Vec<&'a NodeName>, nodes: &Vec<(&'a NodeName, &'a NodeValue)>) -> Vec<(String, String)> {
-            let mut r: Vec<(String, String)> = Vec::new();
-            nodes
-                .iter()
-                .for_each(|(node_name, node_value)| {
-                    name_stack.push(node_name);
-                    match node_value {
-                        NodeValue::Text(t) => {
-                            r.push(
-                                (
-                                    name_stack
-                                        .iter()
-                                        .map(|e| e.to_string())
-                                        .collect::<Vec<String>>()
-                                        .join(crate::DISPLAY_NESTING_STRING),
-                                    t.to_string()
-                                )
-                            );
-                        },
-                        NodeValue::NestedNode(n) => {
-                            r.append(&mut Self::flatten_tree(name_stack, &n.iter().collect()))
-                        }
-                    }
-                    name_stack.pop();
-                });
+        /// Moves the hour selected in the week view by the number of hours,
+        /// staying within the day
+        pub fn move_calendar_hour(&mut self, hours: i32) {
+            self.calendar_hour = self.calendar_hour.saturating_add_signed(hours).min(23);
+        }
 
-            r
+        /// Moves the day selected in the calendar by the number of months
+        pub fn move_calendar_month(&mut self, months: i32) {
+            let moved: Option<NaiveDate> = match months < 0 {
+                true => self.calendar_day.checked_sub_months(Months::new(months.unsigned_abs())),
+                false => self.calendar_day.checked_add_months(Months::new(months as u32)),
+            };
+            self.calendar_day = moved.unwrap_or(self.calendar_day);
+            self.list_state.select(None);
         }
 
-        /// A function that returns the App Element in a Form of Vectors
-        /// where each element is another vector consisting of the key
-        /// and the value. Also sorts the elements
-        pub fn get_vecs(&self) -> Vec<(String, String)> {
-            let mut nodes_sorted: Vec<(&NodeName, &NodeValue)> = self.nodes
+        /// Returns all entries taking place on the day that match the query,
+        /// ordered by their start
+        pub fn entries_on_day(&self, day: NaiveDate) -> Vec<&EntryNode> {
+            let mut entries: Vec<&EntryNode> = self.registry
+                .matching_paths(&|n: &RegistryNode| {
+                    matches!(n, RegistryNode::Element(e) if !e.removed && e.is_on_day(day))
+                        && self.get_query().is_none_or(|q| q.matches(n))
+                        && !self.hides(n)
+                })
                 .iter()
+                .filter_map(|p| match self.registry.node(p)? {
+                    RegistryNode::Element(e) if e.is_on_day(day) => Some(e),
+                    _ => None,
+                })
                 .collect();
-                
-            nodes_sorted.sort_by(|a, b| a.0.order().cmp(&b.0.order()));
+            entries.sort_by_key(|e| e.occurrence_on_day(day).map(|(start, _)| start));
+            entries
+        }
 
-            return Self::flatten_tree(&mut Vec::new(), &nodes_sorted);
+        /// Returns the rows of the agenda in chronological order, as the path of
+        /// the entry with the start and end of the occurrence. Recurring entries
+        /// are listed with their due date and every occurrence in the coming days
+        pub fn agenda_rows(&self) -> Vec<(Vec<usize>, DateTime<Local>, DateTime<Local>)> {
+            let Some((today, _)) = data_helpers::local_day(Local::now().date_naive()) else {
+                return Vec::new();
+            };
+            let mut rows: Vec<(Vec<usize>, DateTime<Local>, DateTime<Local>)> = self.registry
+                .matching_paths(&|n: &RegistryNode| {
+                    matches!(n, RegistryNode::Element(e) if e.due().is_some())
+                        && self.get_query().is_none_or(|q| q.matches(n))
+                        && !self.hides(n)
+                })
+                .into_iter()
+                .filter_map(|p| match self.registry.node(&p) {
+                    Some(RegistryNode::Element(e)) => Some((p, e)),
+                    _ => None,
+                })
+                .flat_map(|(p, e)| {
+                    let Some((start, end)) = e.span() else {
+                        return Vec::new();
+                    };
+                    let mut spans: Vec<(DateTime<Local>, DateTime<Local>)> = match e.recurrence() {
+                        Some(_) => e.occurrences(today, today.max(start) + chrono::Duration::days(crate::AGENDA_RECURRENCE_DAYS)),
+                        None => Vec::new(),
+                    };
+                    if spans.first() != Some(&(start, end)) {
+                        spans.insert(0, (start, end));
+                    }
+                    spans
+                        .into_iter()
+                        .map(|(start, end)| (p.clone(), start, end))
+                        .collect()
+                })
+                .collect();
+            rows.sort_by_key(|(_, start, _)| *start);
+            rows
         }
 
-        /// A function that returns the title followed by the description
-        /// followed by the tags as a single lowercase string, this is designed
-        /// for usage of searching and filtering
-        pub fn get_text(&self) -> String {
-            return String::new() 
-                + &self.nodes.clone().into_values().map(|e| e.to_string()).collect::<Vec<String>>().join(" ")
-                .to_lowercase();
+        /// Returns the index of the saved view in use
+        pub fn get_view(&self) -> Option<usize> {
+            self.view
         }
 
-        /// Generates a new ID for this element. The id will not be in existing ids
-        /// Updates the self element and the existing ids
-        /// Returns the new id
-        pub fn generate_id(&mut self, existing_ids: &mut Vec<u16>) -> u16 {
-            let mut rng = rand::thread_rng();
-            let mut new_id: u16 = 0;
-            while new_id == 0 || existing_ids.iter().any(|&i| i==new_id) {
-                new_id = rng.gen::<u16>();
-            }
-            self.id = Some(new_id);
-            existing_ids.push(new_id);
-            new_id
+        /// Returns the order the listed nodes are sorted in
+        pub fn get_sort(&self) -> &SortOrder {
+            &self.sort
         }
 
-        /// Returns the AppElement as an ListItem
-        pub fn to_list_item<'a>(&self) -> ListItem<'a> {
-            let mut item = ListItem::new(
-                self
-                    .title()
-                    .unwrap_or("<no title>".to_string())
-                    .clone()
-            );
-            if self.removed {
-                item = item.style(Style::default().add_modifier(Modifier::CROSSED_OUT));
-            } else if self.modified {
-                item = item.style(Style::default().add_modifier(Modifier::BOLD));
-            } else if self.id.is_none() {
-                item = item.style(Style::default().add_modifier(Modifier::ITALIC));
+        /// Sorts the listed nodes in the order and remembers it in the
+        /// configuration, keeping the selected node selected. While a saved
+        /// view is in use the order only applies until the view is left
+        fn set_sort(&mut self, sort: SortOrder) {
+            let path: Option<Vec<usize>> = self.get_selected_path();
+            if self.view.is_none() {
+                self.config.sort = sort.to_string();
+            }
+            self.sort = sort;
+            if let Some(p) = path {
+                self.select_path(&p);
             }
-            item
         }
-    }
-
-    /// On which column the current acting focus is
-    #[derive(Debug, PartialEq, Eq)]
-    pub enum AppFocus {
-        Elements,
-        Attributes,
-        Edit
-    }
 
-    impl AppFocus {
-        /// Returns true if the current AppFocus is on Elements
-        pub fn elements(&self) -> bool {
-            self == &Self::Elements
+        /// Sorts by the next of the preset keys ascending
+        pub fn cycle_sort(&mut self) {
+            let presets: Vec<SortKey> = SortKey::presets();
+            let next: SortKey = match presets.iter().position(|k| k == &self.sort.key) {
+                Some(i) => presets[(i + 1) % presets.len()].clone(),
+                None => SortKey::Manual,
+            };
+            self.set_sort(SortOrder { key: next, descending: false });
         }
-        /// Returns true if the current AppFocus is on Attributes
-        pub fn attributes(&self) -> bool {
-            self == &Self::Attributes
+
+        /// Switches between sorting ascending and descending, returns false if
+        /// the order is manual
+        pub fn toggle_sort_direction(&mut self) -> bool {
+            if self.sort.is_manual() {
+                return false;
+            }
+            let sort: SortOrder = SortOrder {
+                key: self.sort.key.clone(),
+                descending: !self.sort.descending,
+            };
+            self.set_sort(sort);
+            true
         }
-        /// Returns true if the current AppFocus is on Edit
-        pub fn edit(&self) -> bool {
-            self == &Self::Edit
+
+        /// Sorts ascending by the currently selected attribute, returns false
+        /// if no attribute is selected
+        pub fn sort_by_selected_attribute(&mut self) -> bool {
+            let name: Option<String> = self
+                .get_selected_node()
+                .zip(self.details_state.selected())
+                .and_then(|(n, i)| Some(n.get_vecs().get(i)?.0.clone()));
+            match name.and_then(|n| SortOrder::parse(&n)) {
+                Some(sort) => {
+                    self.set_sort(sort);
+                    true
+                },
+                None => false,
+            }
         }
-    }
 
-    /// The current state of the app
-    pub struct AppState {
-        config: AppConfig,
-        client: Option<Client>,
-        elements: Vec<EntryNode>,
-        synced: bool,
-        pub focused_on: AppFocus,
-        pub list_state: ListState,
-        pub details_state: TableState,
-        pub prompt: Option<String>,
-        pub message: Option<&'static str>,
-        modification_buffer: Option<String>,
-    }
+        /// Switches to the next saved view from the configuration, after the
+        /// last one all nodes are listed again. Returns the name of the view
+        pub fn select_next_view(&mut self) -> Option<String> {
+            let next: Option<usize> = match self.view {
+                Some(i) if i + 1 < self.config.views.len() => Some(i + 1),
+                Some(_) => None,
+                None if self.config.views.is_empty() => None,
+                None => Some(0),
+            };
+            self.clear_search();
+            let view: SavedView = self.config.views.get(next?)?.clone();
+            self.view = next;
+            self.search = Some(view.filter);
+            self.update_query();
+            self.sort = SortOrder::parse(&view.sort).unwrap_or(self.config.sort_order());
+            self.list_state.select(match self.visible_paths().is_empty() {
+                true => None,
+                false => Some(0),
+            });
+            Some(view.name)
+        }
 
-    impl AppState {
-        pub fn new(config: AppConfig) -> Self {
-            Self {
-                config,
-                client: None,
-                elements: Vec::new(),
-                synced: false,
-                focused_on: AppFocus::Elements,
-                list_state: ListState::default(),
-                details_state: TableState::default(),
-                prompt: None,
-                message: None,
-                modification_buffer: None,
+        /// Stops filtering and sorting the nodes and leaves the saved view,
+        /// keeping the selected node selected
+        pub fn clear_search(&mut self) {
+            let path: Option<Vec<usize>> = self.get_selected_path();
+            self.search = None;
+            self.query = None;
+            self.query_error = None;
+            self.view = None;
+            self.sort = self.config.sort_order();
+            match path {
+                Some(p) => self.select_path(&p),
+                None => self.list_state.select(None),
             }
         }
 
-        /// Checks if we are currently Editing by checking whether the modification
-        /// buffer is some
-        pub fn is_editing(&self) -> bool {
-            return self.modification_buffer.is_some();
+        /// Returns the indices of all visible nodes matching the query
+        fn visible_matches(&self) -> Vec<usize> {
+            let query: &Query = match self.get_query() {
+                Some(q) => q,
+                None => return Vec::new(),
+            };
+            self.get_visible_nodes()
+                .iter()
+                .enumerate()
+                .filter(|(_, (_, n))| query.matches(n))
+                .map(|(i, _)| i)
+                .collect()
         }
 
-        /// Returns All AppElements from the state as a Vec
-        pub fn get_elements(&self) -> Vec<EntryNode> {
-            self.elements.clone()
+        /// Selects the first visible node matching the query
+        fn select_first_match(&mut self) {
+            let indx: Option<usize> = self.visible_matches().first().copied();
+            self.list_state.select(indx);
         }
 
-        /// Returns All AppElements mutable from the state as a Vec
-        pub fn get_elements_mut(&mut self) -> &mut Vec<EntryNode> {
-            &mut self.elements
+        /// Selects the next or the previous node matching the query, wrapping
+        /// around at the end. Returns false if nothing matches
+        pub fn select_match(&mut self, forward: bool) -> bool {
+            let matches: Vec<usize> = self.visible_matches();
+            let current: Option<usize> = self.list_state.selected();
+            let next: Option<&usize> = match (forward, current) {
+                (true, Some(c)) => matches.iter().find(|i| **i > c).or(matches.first()),
+                (false, Some(c)) => matches.iter().rev().find(|i| **i < c).or(matches.last()),
+                (true, None) => matches.first(),
+                (false, None) => matches.last(),
+            };
+            match next {
+                Some(i) => {
+                    self.list_state.select(Some(*i));
+                    true
+                },
+                None => false,
+            }
         }
 
-        /// Returns the currently selected Element if available
-        pub fn get_selected_element(&self) -> Option<&EntryNode> {
-            if let Some(indx) = self.list_state.selected() {
-                self.elements.get(indx)
-            } else {
-                None
-            }
+        /// Returns the content of the directory found at the given path as
+        /// mutable, the top level is returned for an empty path
+        pub fn get_content_mut(&mut self, path: &[usize]) -> Option<&mut Vec<RegistryNode>> {
+            self.registry.content_mut(path)
+        }
+
+        /// Returns the currently selected node if available
+        pub fn get_selected_node(&self) -> Option<&RegistryNode> {
+            self.registry.node(&self.get_selected_path()?)
+        }
+
+        /// Returns the currently selected node as mutable if available
+        pub fn get_selected_node_mut(&mut self) -> Option<&mut RegistryNode> {
+            let path: Vec<usize> = self.get_selected_path()?;
+            self.registry.node_mut(&path)
         }
 
         /// Returns the currently selected Element as mutable if available
         pub fn get_selected_element_mut(&mut self) -> Option<&mut EntryNode> {
-            if let Some(indx) = self.list_state.selected() {
-                self.elements.get_mut(indx)
-            } else {
-                None
+            self.get_selected_node_mut()?.element_mut()
+        }
+
+        /// Expands or collapses the currently selected directory,
+        /// returns false if no directory is selected
+        pub fn toggle_selected_directory(&mut self) -> bool {
+            match self.get_selected_node_mut().and_then(|n| n.directory_mut()) {
+                Some(directory) => {
+                    directory.toggle_expanded();
+                    true
+                },
+                None => false,
             }
         }
 
-        /// Returns the currently selected attribute of the currently selected element
+        /// Returns the currently selected attribute of the currently selected node
         /// if available
         pub fn get_selected_attribute(&self) -> Option<(NodeName, String)> {
-            if let Some(element) = self.get_selected_element() {
+            if let Some(node) = self.get_selected_node() {
                 if let Some(indx) = self.details_state.selected() {
-                    if let Some((k, v)) = element.get_vecs().get(indx) {
-                        return Some((NodeName::from_str(&k), v.to_string()));
+                    if let Some((k, v)) = node.get_vecs().get(indx) {
+                        return Some((NodeName::from_str(k), v.to_string()));
                     };
                 }
             }
             None
         }
 
-        /// Creates a new blank EntryNode, adds it to the current state and returns it
-        pub fn create_new_element(self: &mut AppState) -> &mut EntryNode {
-            let len = self.get_elements().len();
+        /// Creates a new blank EntryNode, adds it to the current state and selects it.
+        /// In the calendar it is due at the start of the selected day, in the
+        /// agenda at the start of the next hour and in the week view it fills
+        /// the selected hour
+        pub fn create_new_element(self: &mut AppState) {
+            let mut nodes: HashMap<NodeName, NodeValue> = HashMap::new();
+            let due: Option<i64> = match self.layout {
+                AppLayout::List => None,
+                AppLayout::Calendar => data_helpers::local_timestamp(self.calendar_day.and_time(NaiveTime::MIN)),
+                AppLayout::Week => {
+                    nodes.insert(NodeName::Duration, NodeValue::Text("60".to_string()));
+                    self.calendar_day
+                        .and_hms_opt(self.calendar_hour, 0, 0)
+                        .and_then(data_helpers::local_timestamp)
+                },
+                AppLayout::Agenda => {
+                    let now: NaiveDateTime = Local::now().naive_local();
+                    now.date()
+                        .and_hms_opt(now.hour(), 0, 0)
+                        .and_then(|hour| data_helpers::local_timestamp(hour + chrono::Duration::hours(1)))
+                },
+            };
+            if let Some(due) = due {
+                nodes.insert(NodeName::Due, NodeValue::Text(due.to_string()));
+            }
             let new_element: EntryNode = EntryNode::new(
                 None,
-                HashMap::new(),
+                nodes,
             );
+            let snapshot: Snapshot = self.snapshot("new event".to_string());
             self.push(Some(new_element));
-            self.list_state.select(Some(len));
-            return self.get_selected_element_mut().expect("FATAL Newly created element not found");
+            self.record(snapshot);
+            let path: Vec<usize> = vec![self.registry.top_level().len() - 1];
+            self.select_path(&path);
+        }
+
+        /// Creates a new blank DirectoryNode next to the currently selected node
+        /// or at the end of the registry if nothing is selected and selects it
+        pub fn create_new_directory(&mut self) {
+            let mut path: Vec<usize> = self.get_selected_path().unwrap_or_default();
+            let indx: usize = match path.pop() {
+                Some(i) => i + 1,
+                None => self.registry.top_level().len(),
+            };
+            let snapshot: Snapshot = self.snapshot("new directory".to_string());
+            if let Some(content) = self.registry.content_mut(&path) {
+                content.insert(indx, RegistryNode::Directory(DirectoryNode::blank()));
+                path.push(indx);
+                self.select_path(&path);
+                self.record(snapshot);
+                self.unsynced();
+            }
         }
 
         /// Gets the current Modification Buffer if we currently edit
         pub fn get_edit(&self) -> Option<String> {
-            return self.modification_buffer.clone();
+            self.modification_buffer.clone()
         }
 
         /// Sets the current Modification Buffer to the specified value
@@ -939,44 +3660,17 @@ pub(crate) mod data_types {
             self.modification_buffer = None;
         }
 
-
-        fn insert_chain_to_map(map: &mut HashMap<NodeName, NodeValue>, mut name_chain: Vec<NodeName>, value: NodeValue) -> Option<NodeValue> {
-            let mut r = None;
-            // TODO: Use VecDeque instead here?
-            let next_name: NodeName = name_chain.remove(0);
-            if name_chain.len() == 0 {
-                r = map.insert(next_name, value);
-            } else {
-                match map.get(&next_name) {
-                    Some(NodeValue::NestedNode(_)) => {},
-                    _ => {
-                        let blank_map: HashMap<NodeName, NodeValue> = HashMap::new();
-                        map.insert(next_name.clone(), NodeValue::NestedNode(blank_map));
-                    }
-                }
-                match map.get_mut(&next_name) {
-                    Some(NodeValue::NestedNode(n)) => {
-                        r = Self::insert_chain_to_map(n, name_chain, value);
-                    },
-                    _ => {}
-                }
-            }
-
-            return r;
-        }
-
         /// Creates a new attribute with empty value inside the currently selected
-        /// element. The name of the new attribute will be the content of the
+        /// node. The name of the new attribute will be the content of the
         /// current modification buffer. Skips if the buffer is None. Resets the
         /// buffer to None afterwards
         pub fn create_new_attribute_from_edit(self: &mut AppState) {
             if let Some(new_name) = &self.modification_buffer.clone() {
-                let new_name_chain: Vec<NodeName> = new_name
-                    .split(crate::DISPLAY_NESTING_STRING)
-                    .map(|e| {NodeName::from_str(e)})
-                    .collect();
-                if let Some(element) = self.get_selected_element_mut() {
-                    Self::insert_chain_to_map(element.nodes(), new_name_chain, NodeValue::Text("".to_string()));
+                let new_name_chain: Vec<NodeName> = data_helpers::name_chain(new_name);
+                let snapshot: Snapshot = self.snapshot(format!("new attribute '{}'", new_name));
+                if let Some(node) = self.get_selected_node_mut() {
+                    data_helpers::insert_chain_to_map(node.nodes(), new_name_chain, NodeValue::Text("".to_string()));
+                    node.modified();
                     /*
                     element
                         .nodes()
@@ -985,6 +3679,7 @@ pub(crate) mod data_types {
                             NodeValue::Text("".to_string())
                         );
                     */
+                    self.record(snapshot);
                     self.unsynced();
                 }
             }
@@ -996,20 +3691,17 @@ pub(crate) mod data_types {
         pub fn save_changes(self: &mut AppState) -> Result<(), ()> {
             let mut new_txt: String = self.get_edit().unwrap_or("".to_string());
             if let Some(node) = self.get_selected_attribute() {
-                let name_chain: Vec<NodeName> = node.0
-                    .to_string()
-                    .split(crate::DISPLAY_NESTING_STRING)
-                    .map(|e| {NodeName::from_str(e)})
-                    .collect();
-                let element: &mut EntryNode = match self.get_selected_element_mut() {
-                    Some(element) => element,
-                    None => self.create_new_element(),
+                let name_chain: Vec<NodeName> = data_helpers::name_chain(&node.0.to_string());
+                let snapshot: Snapshot = self.snapshot(format!("edit of '{}'", node.0));
+                let Some(selected) = self.get_selected_node_mut() else {
+                    return Ok(());
                 };
-                
+
                 new_txt = data_helpers::parse_cmdlet(new_txt)?;
 
-                if Some(NodeValue::Text(new_txt.clone())) != Self::insert_chain_to_map(element.nodes(), name_chain, NodeValue::Text(new_txt)) {
-                    element.modified();
+                if Some(NodeValue::Text(new_txt.clone())) != data_helpers::insert_chain_to_map(selected.nodes(), name_chain, NodeValue::Text(new_txt)) {
+                    selected.modified();
+                    self.record(snapshot);
                     self.unsynced();
                 };
                 self.modification_buffer = None;
@@ -1017,28 +3709,19 @@ pub(crate) mod data_types {
             Ok(())
         }
 
-        /// Finds and Returns an element defined by it's id
-        pub fn get_element_by_id(&mut self, id: u16) -> Option<&mut EntryNode> {
-            self
-                .elements
-                .iter_mut()
-                .find(|e| e.id == Some(id))
-        }
-
         /// Returns all IDs present in the current appstate
         pub fn get_ids(&self, ignore_removed: bool) -> Vec<u16> {
-            return self.elements
-                .clone()
+            self.registry
+                .entries()
                 .into_iter()
                 .filter(|e| !e.removed && ignore_removed)
                 .filter_map(|e| e.id)
-                .collect();
-
+                .collect()
         }
 
         pub fn push(&mut self, element: Option<EntryNode>) {
             if let Some(e) = element {
-                self.elements.push(e)
+                self.registry.push(RegistryNode::Element(e))
             }
         }
 
@@ -1046,380 +3729,824 @@ pub(crate) mod data_types {
             self.synced = false;
         }
 
-        pub fn sort_by_due(&mut self) {
-            self.elements.sort_by(|a, b| {
-                match a.due() {
-                    Some(due_a) => {
-                        match b.due() {
-                            Some(due_b) => {due_a.cmp(&due_b)},
-                            None => {due_a.cmp(&0)}
-                        }
-                    },
-                    None => {
-                        match b.due() {
-                            Some(due_b) => {due_b.cmp(&0)},
-                            None => {0.cmp(&0)}
-                        }
-                    }
-                }
-            })
-        }
-
         /// Returns a string that supposes to indicate whether modifications
         /// have been made to the local state
         pub fn modified_string(&self) -> String {
-            if self.is_editing() {
+            let status: &str = if self.is_editing() {
                 "editing"
+            } else if let Some(phase) = &self.sync_phase {
+                return match self.last_sync {
+                    Some(time) => format!("{}... (last sync {})", phase, time.format("%H:%M:%S")),
+                    None => format!("{}...", phase),
+                };
             } else {
                 match self.synced {
                     true => "synced",
                     false => "edited",
+                }
+            };
+            match self.last_sync {
+                Some(time) => format!("{} (last sync {})", status, time.format("%H:%M:%S")),
+                None => status.to_string(),
+            }
+        }
+
+        /// Returns the configuration in use
+        pub fn config(&self) -> &AppConfig {
+            &self.config
+        }
+
+        /// Returns the name of the server profile in use
+        pub fn profile_name(&self) -> &str {
+            self.config.active_profile_name()
+        }
+
+        /// Returns the time between two syncs in the background if enabled
+        pub fn sync_interval(&self) -> Option<Duration> {
+            match self.config.sync_interval {
+                0 => None,
+                s => Some(Duration::from_secs(s)),
             }
-            }.to_string()
         }
 
-        fn handle_empty_client(&mut self) {
+        fn handle_empty_client(&mut self) -> Result<&Client, AppError> {
             if self.client.is_none() {
                 self.client = Some(
                     Client::builder()
                         .user_agent("Freemind CLI")
-                        .build().unwrap()
+                        .build()?
                 );
             }
+            self.client.as_ref().ok_or(AppError::Config("No HTTP client available".to_string()))
         }
 
-        /// Adds non existing elements to the State of elements, skips
-        /// already existing elements and returns the result
-        fn add_new_elements(&mut self, new: Vec<&EntryNode>) {
-            new.into_iter().for_each(|e| {
-                if self.elements.iter().any(|i| e == i) {
+        /// Obtains the secret from its source once and keeps it for all
+        /// further connections
+        fn handle_empty_secret(&mut self) -> Result<&str, AppError> {
+            if self.secret.is_none() {
+                self.secret = Some(self.config.active().obtain_secret()?);
+            }
+            self.secret.as_deref().ok_or(AppError::Secret("No secret available".to_string()))
+        }
 
-                } else {
-                    self.elements.push(e.clone())
-                }
+        /// Returns a connection to the configured server
+        pub fn connection(&mut self) -> Result<Connection, AppError> {
+            let client: Client = self.handle_empty_client()?.clone();
+            let secret: String = self.handle_empty_secret()?.to_string();
+            Ok(Connection {
+                client,
+                server: self.config.active().clone(),
+                secret,
             })
         }
 
-        /// Generates IDs for all elements in the current state that don't already
-        /// have one. Needs a full list of existing IDs to avoid during generation
-        fn add_missing_ids(&mut self, existing_ids: &mut Vec<u16>) -> (bool, Vec<u16>) {
-            let mut new_ids: Vec<u16> = Vec::new();
-            let count_after: usize = self.elements
-                .iter_mut()
-                .filter(|e| e.id.is_none())
-                .map(|e| {
-                    new_ids.push(e.generate_id(existing_ids))
-                }).count();
-            (count_after != 0, new_ids)
+        /// Returns the phase of the running sync if any
+        pub fn get_sync_phase(&self) -> Option<&SyncPhase> {
+            self.sync_phase.as_ref()
         }
 
-        /// Makes a call to the configured server using the provided endpoint
-        async fn call(&mut self, endpoint: &str, payload: String) -> Result<Response, reqwest::Error> {
-            self.handle_empty_client();
-            let res: Response = self.client.as_ref().unwrap()
-                .post(format!("{}{}", self.config.server_address, endpoint))
-                .header(
-                    "user".to_string(),
-                    HeaderValue::from_str(&self.config.username).unwrap()
-                )
-                .header(
-                    format!("{}", &self.config.auth_method).to_lowercase(),
-                    &self.config.secret
-                )
-                .header(
-                    "content-type".to_string(),
-                    "text/xml".to_string(),
-                )
-                .body(payload)
-                .send()
-                .await?;
+        /// Sets the task running the given phase of the sync in the background
+        pub fn set_sync_task(&mut self, phase: SyncPhase, task: JoinHandle<SyncEvent>) {
+            self.sync_phase = Some(phase);
+            self.sync_task = Some(task);
+        }
 
-            Ok(res)
+        /// Takes the task of the running sync if it is finished
+        pub fn take_finished_sync_task(&mut self) -> Option<JoinHandle<SyncEvent>> {
+            if self.sync_task.as_ref().is_some_and(|t| t.is_finished()) {
+                return self.sync_task.take();
+            }
+            None
         }
 
-        /// Fetches the whole registry from the server
-        async fn fetch(&mut self) -> Result<String, reqwest::Error> {
-            let res: Response = self.call("/xml/fetch", "".to_string()).await?;
+        /// Stops the running sync, local changes are kept to be pushed with
+        /// the next sync
+        pub fn cancel_sync(&mut self) {
+            if let Some(task) = self.sync_task.take() {
+                task.abort();
+            }
+            self.sync_phase = None;
+            self.pushed = None;
+        }
+
+        /// Generates IDs for all nodes in the current state that don't already
+        /// have one. Needs a full list of existing IDs to avoid during generation
+        fn add_missing_ids(&mut self, existing_ids: &mut Vec<u16>) -> (bool, Vec<u16>) {
+            let new_ids: Vec<u16> = self.registry.add_missing_ids(existing_ids);
+            (!new_ids.is_empty(), new_ids)
+        }
+
+        pub fn is_synced(&self) -> bool {
+            self.synced
+        }
 
-            let headers = res.headers();
-            if headers.get("content-type") == Some(&HeaderValue::from_static("text/xml")) {
-                let txt = res.text().await?;
-                return Ok(txt);
+        /// Shows the earliest pending alert that was neither dismissed nor
+        /// snoozed, unless something is shown already. Returns whether an alert
+        /// was shown
+        pub fn check_alerts(&mut self) -> bool {
+            if self.alert.is_some() || self.prompt.is_some() {
+                return false;
             }
+            let now: DateTime<Local> = Local::now();
+            let alert: Option<Alert> = Alert::pending(
+                &self.registry.entries(),
+                now,
+                chrono::Duration::minutes(crate::ALERT_WINDOW_MINUTES),
+                |id, at| self.dismissed.contains(&(id, at))
+                    || self.snoozed.get(&(id, at)).is_some_and(|until| until > &now)
+            );
+            let Some(alert) = alert else {
+                return false;
+            };
+            self.prompt = Some(format!("Alert!\n\n{}", alert));
+            self.alert = Some(alert);
+            true
+        }
 
-            Ok(String::new())
+        /// Returns the alert currently shown
+        pub fn get_alert(&self) -> Option<&Alert> {
+            self.alert.as_ref()
         }
 
-        /// Uploads the given payload to the server and returns the HTTP status code
-        async fn upload(&mut self, payload: String) -> Result<u16, reqwest::Error> {
-            let res: Response = self.call("/xml/update", payload).await?;
+        /// Closes the alert currently shown for good
+        pub fn dismiss_alert(&mut self) {
+            if let Some(alert) = self.alert.take() {
+                self.dismissed.insert((alert.id, alert.at));
+                self.prompt = None;
+            }
+        }
 
-            let status = res.status().as_u16();
+        /// Closes the alert currently shown and shows it again once the snooze
+        /// time from the config has passed
+        pub fn snooze_alert(&mut self) {
+            if let Some(alert) = self.alert.take() {
+                let until: DateTime<Local> = Local::now() + chrono::Duration::minutes(self.config.alert_snooze as i64);
+                self.snoozed.insert((alert.id, alert.at), until);
+                self.prompt = None;
+            }
+        }
 
-            return Ok(status)
+        /// Serializes the dismissed alerts with one 'id timestamp' pair per line,
+        /// leaving out the ones that are too old to fire again
+        pub fn dismissed_to_string(&self) -> String {
+            let oldest: i64 = Local::now().timestamp() - crate::ALERT_KEEP_DAYS * 24 * 60 * 60;
+            self.dismissed
+                .iter()
+                .filter(|(_, at)| *at >= oldest)
+                .map(|(id, at)| format!("{} {}\n", id, at))
+                .collect()
         }
 
-        /// Takes the whole XML Document and removes all Entries that were removed
-        /// in the internal state.
-        /// Returns whether changes where made and the string of the new payload
-        fn delete_removed(&mut self, xml: String) -> Result<(bool, String), quick_xml::Error> {
-            let mut modified = false;
+        /// Restores the dismissed alerts, skipping lines that can't be parsed
+        pub fn load_dismissed(&mut self, dismissed: &str) {
+            self.dismissed.extend(dismissed
+                .lines()
+                .filter_map(|l| {
+                    let (id, at) = l.trim().split_once(' ')?;
+                    Some((id.parse::<u16>().ok()?, at.parse::<i64>().ok()?))
+                })
+            );
+        }
 
-            let mut reader = Reader::from_str(&xml);
+        /// Serializes the local registry with all unsynced changes and the base
+        /// of the last sync into a document for the offline cache
+        pub fn to_cache(&self) -> Result<String, quick_xml::Error> {
             let mut writer = Writer::new(Cursor::new(Vec::new()));
-            
+            writer.write_event(Event::Start(BytesStart::new("cache")))?;
+
+            self.registry.write_state(&mut writer)?;
+
+            let mut base_ids: Vec<&u16> = self.base.keys().collect();
+            base_ids.sort();
+            writer.write_event(Event::Start(BytesStart::new("base")))?;
+            for id in base_ids {
+                let mut head: BytesStart<'_> = state_head("entry", Some(*id), &[]);
+                if let Some(Some(parent)) = self.base_parents.get(id) {
+                    head.push_attribute(("parent", parent.to_string().as_str()));
+                }
+                writer.write_event(Event::Start(head))?;
+                write_attributes(&mut writer, &self.base[id])?;
+                writer.write_event(Event::End(BytesEnd::new("entry")))?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("base")))?;
+
+            writer.write_event(Event::Start(BytesStart::new("conflicts")))?;
+            for conflict in &self.conflicts {
+                let mut head: BytesStart<'_> = state_head("conflict", Some(conflict.id), &[("moved", conflict.parents.is_some())]);
+                if let Some(parents) = conflict.parents {
+                    [("base-parent", parents.base), ("local-parent", parents.local), ("remote-parent", parents.remote)]
+                        .into_iter()
+                        .filter_map(|(name, parent)| Some((name, parent?)))
+                        .for_each(|(name, parent)| head.push_attribute((name, parent.to_string().as_str())));
+                }
+                let mut nodes: HashMap<NodeName, NodeValue> = HashMap::from([
+                    (NodeName::Title, NodeValue::Text(conflict.title.clone())),
+                    (NodeName::from_str("attribute"), NodeValue::Text(conflict.attribute.clone())),
+                ]);
+                [("base", &conflict.base), ("local", &conflict.local), ("remote", &conflict.remote)]
+                    .into_iter()
+                    .filter_map(|(name, value)| Some((name, value.clone()?)))
+                    .for_each(|(name, value)| {
+                        nodes.insert(NodeName::from_str(name), NodeValue::Text(value));
+                    });
+                writer.write_event(Event::Start(head))?;
+                write_attributes(&mut writer, &nodes)?;
+                writer.write_event(Event::End(BytesEnd::new("conflict")))?;
+            }
+            writer.write_event(Event::End(BytesEnd::new("conflicts")))?;
+
+            writer.write_event(Event::End(BytesEnd::new("cache")))?;
+            Ok(String::from_utf8_lossy(&writer.into_inner().into_inner()).to_string())
+        }
+
+        /// Restores the local registry, the base and the unresolved conflicts
+        /// from the offline cache, the state is unsynced if the cache contains
+        /// any local changes or conflicts
+        pub fn load_cache(&mut self, cache: &str) -> Result<(), quick_xml::Error> {
+            let mut reader: Reader<&[u8]> = Reader::from_str(cache);
             reader.trim_text(true);
 
-            let mut ffwd: bool = false;
-            let mut skip: BytesStart = BytesStart::new("");
+            let mut buf: Vec<u8> = Vec::new();
+
+            let mut registry: Registry = Registry::empty();
+            let mut base: HashMap<u16, HashMap<NodeName, NodeValue>> = HashMap::new();
+            let mut base_parents: HashMap<u16, Option<u16>> = HashMap::new();
+            let mut conflicts: Vec<Conflict> = Vec::new();
 
             loop {
-                match reader.read_event() {
-                    Ok(Event::Start(_)) if ffwd => {
-                        continue
-                    }
-                    Ok(Event::Start(e)) if e.name().as_ref() == b"entry" => {
-                        let mut write: bool = true;
-                        if let Some(v) = data_helpers::get_id_attribute(&reader, &e) {
-                            if let Some(pos) = self.elements.iter().position(|e| e.id == Some(v)) {
-                                if self.elements[pos].removed {
-                                    self.elements.remove(pos);
-                                    ffwd = true;
-                                    skip = e.to_owned();
-                                    modified = true;
-                                    write = false;
-                                };
-                            };
-                        }
-                        if write {
-                            writer.write_event(Event::Start(e.to_owned()))?;
-                        }
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) if e.name().as_ref() == b"registry" => {
+                        let (nodes, content) = Registry::read_content(&mut reader, b"registry", true)?;
+                        registry = Registry { nodes, content };
                     },
-                    Ok(Event::Start(e)) => {
-                        writer.write_event(Event::Start(e.to_owned()))?;
-                    }
-                    Ok(Event::End(e)) if e == skip.to_end() => {
-                        ffwd = false;
-                        skip = BytesStart::new("");
-                    }
-                    Ok(Event::End(_)) if ffwd => {
-                        continue
-                    }
-                    Ok(Event::End(e)) => {
-                        writer.write_event(Event::End(e.to_owned()))?;
+                    Event::Start(e) if e.name().as_ref() == b"base" => {
+                        Self::read_base(&mut reader, &mut base, &mut base_parents)?;
                     },
-                    Ok(Event::Eof) => break,
-                    Ok(_) if ffwd => {
-                        continue
+                    Event::Start(e) if e.name().as_ref() == b"conflicts" => {
+                        Self::read_conflicts(&mut reader, &mut conflicts)?;
                     },
-                    Ok(e) => {
-                        writer.write_event(e)?;
-                    }
-                    Err(_) => break,
-                    //_ => (),
+                    Event::Eof => break,
+                    _ => (),
                 }
             }
 
-            Ok((
-                modified,
-                str::from_utf8(
-                    &writer.into_inner().into_inner()
-                )
-                .unwrap()
-                .to_string()
-            ))
+            self.synced = !registry.has_local_changes() && conflicts.is_empty();
+            self.registry = registry;
+            self.base = base;
+            self.base_parents = base_parents;
+            self.conflicts = conflicts;
+            self.conflict_state.select(None);
+            Ok(())
         }
 
-        /// Takes the whole XML Document and inserts Entries defined by the ids vec into it
-        fn insert_created_entries(&self, xml: String, ids: Vec<u16>) -> String {
-            let mut reader = Reader::from_str(&xml);
-            let mut writer = Writer::new(Cursor::new(Vec::new()));
+        /// Reads the unresolved conflicts until the closing conflicts tag of
+        /// the offline cache is reached
+        fn read_conflicts(reader: &mut Reader<&[u8]>, conflicts: &mut Vec<Conflict>) -> Result<(), quick_xml::Error> {
+            let mut buf: Vec<u8> = Vec::new();
 
             loop {
-                match reader.read_event() {
-                    Ok(Event::Start(e)) if e.name().as_ref() == b"registry" => {
-                        writer.write_event(Event::Start(e.to_owned())).unwrap();
-                        self.elements
-                            .iter()
-                            .filter(|e| ids.iter().any(|i| Some(i) == e.id.as_ref()))
-                            .map(|e| {
-                                e.write(&mut writer, true).unwrap();
-                            }).count();
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) if e.name().as_ref() == b"conflict" => {
+                        let id: Option<u16> = data_helpers::get_id_attribute(reader, &e);
+                        let parent = |name: &[u8]| data_helpers::get_number_attribute(reader, &e, name);
+                        let parents: Option<ConflictParents> = match data_helpers::get_flag_attribute(reader, &e, b"moved") {
+                            true => Some(ConflictParents { base: parent(b"base-parent"), local: parent(b"local-parent"), remote: parent(b"remote-parent") }),
+                            false => None,
+                        };
+                        let (nodes, _) = Registry::read_content(reader, b"conflict", false)?;
+                        let text = |name: &str| nodes.get(&NodeName::from_str(name)).map(|v| v.to_string());
+                        if let Some(id) = id {
+                            conflicts.push(Conflict {
+                                id,
+                                title: text("name").unwrap_or_default(),
+                                attribute: text("attribute").unwrap_or_default(),
+                                base: text("base"),
+                                local: text("local"),
+                                remote: text("remote"),
+                                parents,
+                            });
+                        }
                     },
-                    Ok(Event::Eof) => break,
-                    Ok(e) => {writer.write_event(e).unwrap();}
-                    Err(_) => break,
+                    Event::End(e) if e.name().as_ref() == b"conflicts" => break,
+                    Event::Eof => break,
+                    _ => (),
                 }
             }
 
-            str::from_utf8(
-                &writer
-                .into_inner()
-                .into_inner()
-            ).unwrap().to_string()
+            Ok(())
         }
 
-        /// TODO: CHANGE THIS IT DOES NOT WORK PROPERLY ANYMORE
-        /// Takes the whole XML Document and edits Entries that are marked to be
-        /// in an edited state
-        fn edit_entries(&mut self, xml: String) -> Result<(bool, String), quick_xml::Error> {
-            let mut modified = false;
-
-            let mut reader = Reader::from_str(&xml);
-            let mut writer = Writer::new(Cursor::new(Vec::new()));
-
-            let mut change_element: Option<u16> = None;
-            let mut skip: BytesStart = BytesStart::new("");
-            let mut skip_subtag: BytesStart = BytesStart::new("");
+        /// Reads the attributes and the directory of every node at the last
+        /// sync until the closing base tag of the offline cache is reached
+        fn read_base(reader: &mut Reader<&[u8]>, base: &mut HashMap<u16, HashMap<NodeName, NodeValue>>, base_parents: &mut HashMap<u16, Option<u16>>) -> Result<(), quick_xml::Error> {
+            let mut buf: Vec<u8> = Vec::new();
 
             loop {
-                match reader.read_event() {
-                    Ok(Event::Start(e)) => {
-                        if let Some(element) = self.get_element_by_id(change_element.unwrap_or(0)) {
-                            if element
-                                .nodes
-                                .contains_key(
-                                    &NodeName::from_str(
-                                        str::from_utf8(e
-                                            .name()
-                                            .as_ref()
-                                        ).unwrap_or("")
-                                    )
-                                ) {
-                                    skip_subtag = e.to_owned();
-                                } else {
-                                    if skip_subtag == BytesStart::new("") {
-                                        writer.write_event(Event::Start(e.to_owned()))?
-                                    }    
-                                }
-                        } else if e.name().as_ref() == b"entry" {
-                            writer.write_event(Event::Start(e.to_owned())).unwrap();
-
-                            if let Some(id) = data_helpers::get_id_attribute(&reader, &e) {
-                                if let Some(element) = self.get_element_by_id(id) {
-                                    if element.modified {
-                                        element.modified = false;
-                                        modified = true;
-                                        change_element = Some(id);
-                                        skip = e.to_owned();
-
-                                        element.write(&mut writer, false)?;
-                                    }
-                                };
-                            };
-                        } else {
-                            writer.write_event(Event::Start(e.to_owned())).unwrap();
+                match reader.read_event_into(&mut buf)? {
+                    Event::Start(e) if e.name().as_ref() == b"entry" => {
+                        let id: Option<u16> = data_helpers::get_id_attribute(reader, &e);
+                        let parent: Option<u16> = data_helpers::get_number_attribute(reader, &e, b"parent");
+                        let (attributes, _) = Registry::read_content(reader, b"entry", false)?;
+                        if let Some(id) = id {
+                            base.insert(id, attributes);
+                            base_parents.insert(id, parent);
                         }
                     },
-                    Ok(Event::End(e)) if e == skip_subtag.to_end() => {
-                        skip_subtag = BytesStart::new("");
-                    }
-                    Ok(Event::End(e)) if e == skip.to_end() => {
-                        change_element = None;
-                        skip = BytesStart::new("");
-                        writer.write_event(Event::End(e.to_owned()))?;
-                    },
-                    Ok(Event::Eof) => break,
-                    Ok(e) => if skip_subtag == BytesStart::new("") {
-                        writer.write_event(e)?;
-                    }
-                    Err(_) => break,
-                    //_ => (),
+                    Event::End(e) if e.name().as_ref() == b"base" => break,
+                    Event::Eof => break,
+                    _ => (),
                 }
             }
 
-            Ok((
-                modified,
-                str::from_utf8(
-                    &writer.into_inner().into_inner()
-                )
-                .unwrap()
-                .to_string()
-            ))
+            Ok(())
         }
 
-        pub fn is_synced(&self) -> bool {
-            self.synced
+        /// Returns all conflicts that are left to be resolved
+        pub fn get_conflicts(&self) -> &Vec<Conflict> {
+            &self.conflicts
         }
 
-        /// Syncs changes, fetches new elements, deletes removed elements and pushes
-        pub async fn sync(&mut self) -> Result<(), reqwest::Error> {
-            let result = self.fetch().await?;
+        /// Merges the freshly fetched registry into the local state. Nodes that
+        /// were not edited locally take the remote attributes, nodes edited on
+        /// both sides are merged attribute by attribute against the base taken
+        /// at the last sync. Conflicting attributes keep their local value and
+        /// are collected to be resolved by the user
+        fn merge_remote(&mut self, remote: &Registry) {
+            let mut conflicts: Vec<Conflict> = Vec::new();
+            let mut dropped: Vec<u16> = Vec::new();
+            let empty: HashMap<NodeName, NodeValue> = HashMap::new();
+            let base = &self.base;
+
+            Registry::for_each_node_mut(&mut self.registry.content, &mut |node| {
+                let Some(id) = node.id() else {
+                    return;
+                };
+                match remote.find(id) {
+                    Some(_) if node.removed() => {},
+                    Some(remote_node) if !node.is_modified() => {
+                        *node.nodes() = remote_node.attributes().clone();
+                    },
+                    Some(remote_node) => {
+                        let node_base: &HashMap<NodeName, NodeValue> = base.get(&id).unwrap_or(&empty);
+                        let (merged, names) = data_helpers::merge_attributes(
+                            node_base,
+                            node.attributes(),
+                            remote_node.attributes()
+                        );
+                        let local: HashMap<String, String> = data_helpers::flatten_sorted(node.attributes()).into_iter().collect();
+                        let remote_values: HashMap<String, String> = data_helpers::flatten_sorted(remote_node.attributes()).into_iter().collect();
+                        let base_values: HashMap<String, String> = data_helpers::flatten_sorted(node_base).into_iter().collect();
+                        let title: String = node.title().unwrap_or("<no name>".to_string());
+                        names
+                            .into_iter()
+                            .for_each(|name| conflicts.push(Conflict {
+                                id,
+                                title: title.clone(),
+                                base: base_values.get(&name).cloned(),
+                                local: local.get(&name).cloned(),
+                                remote: remote_values.get(&name).cloned(),
+                                attribute: name,
+                                parents: None,
+                            }));
+                        let in_sync: bool = data_helpers::flatten_sorted(&merged) == data_helpers::flatten_sorted(remote_node.attributes());
+                        *node.nodes() = merged;
+                        if in_sync {
+                            node.unmodified();
+                        }
+                    },
+                    // Removed on the server, unless it was edited locally in the meantime
+                    None if node.removed() || (!node.is_modified() && !node.is_moved() && base.contains_key(&id)) => {
+                        dropped.push(id);
+                    },
+                    // Edited locally but removed on the server, so it has to be inserted again
+                    None => node.set_moved(true),
+                }
+            });
 
-            let (entries_deleted, answer) = self
-                .delete_removed(result.to_string())
-                .unwrap_or((false, result));
+            dropped
+                .into_iter()
+                .for_each(|id| {
+                    self.registry.drop_node(id);
+                    self.base.remove(&id);
+                    self.base_parents.remove(&id);
+                });
 
-            //let entries_modified = false;
-            let (entries_modified, mut answer) = self.edit_entries(answer).unwrap();
+            self.registry.add_new_nodes(remote);
+            self.merge_parents(remote, &mut conflicts);
+            self.conflicts = conflicts;
+        }
 
-            let fetched_registry: Registry = Registry::empty().from_string(&answer).unwrap();
+        /// Returns the title of the directory with the given ID to show where
+        /// a node is located, the top level is shown as the nesting string
+        fn parent_title(&self, parent: Option<u16>) -> String {
+            match parent {
+                Some(id) => self.registry.find(id).and_then(|n| n.title()).unwrap_or("<no name>".to_string()),
+                None => crate::DISPLAY_NESTING_STRING.to_string(),
+            }
+        }
 
-            let mut existing_ids: Vec<u16> = fetched_registry.entries()
-                .clone()
-                .into_iter()
-                .filter(|e| !e.removed)
-                .filter_map(|e| e.id)
+        /// Moves every node the server moved to another directory there as
+        /// well, unless it was moved locally since the last sync. Nodes moved
+        /// to different directories on both sides, or to a directory they
+        /// can't be moved to, stay where they are and are collected as conflicts
+        fn merge_parents(&mut self, remote: &Registry, conflicts: &mut Vec<Conflict>) {
+            let local_parents: HashMap<u16, Option<u16>> = self.registry.parents();
+            let remote_parents: HashMap<u16, Option<u16>> = remote.parents();
+            let mut ids: Vec<u16> = local_parents
+                .keys()
+                .filter(|id| remote_parents.contains_key(id))
+                .copied()
                 .collect();
+            ids.sort();
+
+            for id in ids {
+                let (local, remote_parent) = (local_parents[&id], remote_parents[&id]);
+                let Some((removed, moved, title)) = self.registry.find(id).map(|n| (n.removed(), n.is_moved(), n.title())) else {
+                    continue;
+                };
+                if local == remote_parent || removed {
+                    continue;
+                }
+                let (moved_locally, moved_remotely): (bool, bool) = match self.base_parents.get(&id) {
+                    Some(base) => (*base != local, *base != remote_parent),
+                    None => (moved, !moved),
+                };
+                if !moved_remotely {
+                    // The local directory is pushed with the next sync
+                    if let Some(node) = self.registry.find_mut(id) {
+                        node.set_moved(true);
+                    }
+                    continue;
+                }
+                if !moved_locally && self.registry.move_node(id, remote_parent) {
+                    continue;
+                }
+                let base: Option<u16> = self.base_parents.get(&id).copied().unwrap_or(local);
+                conflicts.push(Conflict {
+                    id,
+                    title: title.unwrap_or("<no name>".to_string()),
+                    attribute: crate::CONFLICT_DIRECTORY_ATTRIBUTE.to_string(),
+                    base: Some(self.parent_title(base)),
+                    local: Some(self.parent_title(local)),
+                    remote: Some(self.parent_title(remote_parent)),
+                    parents: Some(ConflictParents { base, local, remote: remote_parent }),
+                });
+            }
+        }
+
+        /// Takes the attributes of all nodes without conflicts as they are on
+        /// the server as the base for the next merge
+        fn update_base(&mut self, server: &Registry) {
+            let mut base: HashMap<u16, HashMap<NodeName, NodeValue>> = HashMap::new();
+            let conflicted: Vec<u16> = self.conflicts.iter().filter(|c| c.parents.is_none()).map(|c| c.id).collect();
+            Registry::for_each_node(&server.content, &mut |n| {
+                if let Some(id) = n.id() {
+                    let attributes: HashMap<NodeName, NodeValue> = match (conflicted.contains(&id), self.base.get(&id)) {
+                        (true, Some(b)) => b.clone(),
+                        (true, None) => HashMap::new(),
+                        (false, _) => n.attributes().clone(),
+                    };
+                    base.insert(id, attributes);
+                }
+            });
+            self.base = base;
+
+            let mut base_parents: HashMap<u16, Option<u16>> = server.parents();
+            self.conflicts
+                .iter()
+                .filter_map(|c| Some((c.id, c.parents?)))
+                .for_each(|(id, parents)| {
+                    base_parents.insert(id, parents.base);
+                });
+            self.base_parents = base_parents;
+        }
+
+        /// Resolves the currently selected conflict by keeping the chosen value.
+        /// The remote value becomes the new base of the attribute, so the
+        /// choice is pushed with the next sync
+        pub fn resolve_conflict(&mut self, choice: ConflictChoice) -> bool {
+            let Some(indx) = self.conflict_state.selected().filter(|i| *i < self.conflicts.len()) else {
+                return false;
+            };
+            let conflict: Conflict = self.conflicts.remove(indx);
+
+            if let Some(parents) = conflict.parents {
+                self.base_parents.insert(conflict.id, parents.remote);
+                let chosen: Option<u16> = match choice {
+                    ConflictChoice::Local => parents.local,
+                    ConflictChoice::Remote => parents.remote,
+                    ConflictChoice::Base => parents.base,
+                };
+                let parent: Option<u16> = match chosen == parents.local || self.registry.move_node(conflict.id, chosen) {
+                    true => chosen,
+                    false => parents.local,
+                };
+                if let Some(node) = self.registry.find_mut(conflict.id) {
+                    node.set_moved(parent != parents.remote);
+                }
+            } else {
+                let base: &mut HashMap<NodeName, NodeValue> = self.base.entry(conflict.id).or_default();
+                match &conflict.remote {
+                    Some(v) => data_helpers::insert_chain_to_map(base, data_helpers::name_chain(&conflict.attribute), NodeValue::Text(v.to_string())),
+                    None => data_helpers::remove_chain_from_map(base, data_helpers::name_chain(&conflict.attribute)),
+                };
 
-            let (entries_added, new_ids) = self.add_missing_ids(&mut existing_ids);
+                let value: Option<String> = match choice {
+                    ConflictChoice::Local => conflict.local,
+                    ConflictChoice::Remote => conflict.remote,
+                    ConflictChoice::Base => conflict.base,
+                };
+                if let Some(node) = self.registry.find_mut(conflict.id) {
+                    match value {
+                        Some(v) => data_helpers::insert_chain_to_map(node.nodes(), data_helpers::name_chain(&conflict.attribute), NodeValue::Text(v)),
+                        None => data_helpers::remove_chain_from_map(node.nodes(), data_helpers::name_chain(&conflict.attribute)),
+                    };
+                    node.modified();
+                }
+            }
 
-            if entries_added {
-                answer = self.insert_created_entries(answer, new_ids);
+            if indx >= self.conflicts.len() {
+                self.conflict_state.select(self.conflicts.len().checked_sub(1));
             }
+            self.unsynced();
+            true
+        }
+
+        /// Builds the registry to push from the merged local registry. Nodes
+        /// with unresolved conflicts are kept as they are on the server
+        fn build_payload(&self, remote: &Registry) -> Registry {
+            let mut payload: Registry = self.registry.clone();
+            self.conflicts
+                .iter()
+                .for_each(|c| match c.parents {
+                    Some(parents) => {
+                        payload.move_node(c.id, parents.remote);
+                    },
+                    None => {
+                        if let (Some(node), Some(remote_node)) = (payload.find_mut(c.id), remote.find(c.id)) {
+                            *node.nodes() = remote_node.attributes().clone();
+                        }
+                    },
+                });
+            payload
+        }
+
+        /// Merges the fetched registry into the local state and returns the
+        /// payload to push if anything changed locally
+        pub fn merge_fetched(&mut self, xml: &str) -> Result<Option<String>, AppError> {
+            self.sync_phase = Some(SyncPhase::Merging);
+
+            let remote: Registry = Registry::from_string(xml)?;
+
+            let placements: HashMap<u64, Placement> = self.registry.placements();
+            self.merge_remote(&remote);
+            self.forget_remote_changes(&placements);
+            self.registry.sort_by_position();
+
+            self.registry.remove_unsynced();
+
+            // Nodes only known locally have to be avoided as well
+            let mut existing_ids: Vec<u16> = remote.ids();
+            existing_ids.append(&mut self.registry.ids());
 
-            let needs_upload: bool = entries_deleted || entries_modified || entries_added;
+            let (entries_added, _) = self.add_missing_ids(&mut existing_ids);
 
-            if needs_upload {
-                self.upload(answer.clone()).await?;
+            if !entries_added && !self.registry.has_local_changes() {
+                self.pushed = Some(remote);
+                return Ok(None);
             }
 
+            let payload: Registry = self.build_payload(&remote);
+            let xml: String = payload.to_xml()?;
+            self.pushed = Some(payload);
+            Ok(Some(xml))
+        }
 
-            self.add_new_elements(fetched_registry.entries());
+        /// Finishes the running sync after the merged registry was pushed.
+        /// Changes made locally while the sync was running are kept
+        pub fn finish_sync(&mut self) {
+            let pushed: Registry = self.pushed.take().unwrap_or(Registry::empty());
 
-            self.sort_by_due();
+            let conflicted: Vec<u16> = self.conflicts.iter().map(|c| c.id).collect();
+            self.registry.clear_local_changes(&pushed, &conflicted);
 
-            self.synced = true;
-            Ok(())
+            self.update_base(&pushed);
+
+            self.synced = self.conflicts.is_empty() && !self.registry.has_local_changes();
+            self.last_sync = Some(Local::now());
+            self.sync_phase = None;
         }
 
         pub fn remove(&mut self, id: u16) -> bool {
-            let Some(posi) = self.elements.iter().position(|e| e.id == Some(id)) else {return false};
-            self.elements[posi].removed = true;
+            let Some(element) = self.registry.find_mut(id).and_then(|n| n.element_mut()) else {return false};
+            element.removed = true;
             true
         }
 
-        /// Removes the currently selected element, returns true on successful removal
+        /// Toggles the completion of the selected entry, keeping the selection
+        /// in the list if the entry is hidden now
+        pub fn toggle_done(&mut self) -> Option<Completion> {
+            let snapshot: Snapshot = self.snapshot(format!("completion of '{}'", self.selected_title()));
+            let completion: Completion = self.get_selected_element_mut()?.toggle_done(Local::now());
+            self.record(snapshot);
+            self.unsynced();
+            let len: usize = self.visible_paths().len();
+            if self.list_state.selected().is_some_and(|i| i >= len) {
+                self.list_state.select(len.checked_sub(1));
+            }
+            Some(completion)
+        }
+
+        /// Moves the selected recurring entry on to its next occurrence, returns
+        /// false if nothing recurring is selected
+        pub fn skip_occurrence(&mut self) -> bool {
+            let snapshot: Snapshot = self.snapshot(format!("skip of '{}'", self.selected_title()));
+            let skipped: bool = self.get_selected_element_mut().is_some_and(|e| e.advance_recurrence());
+            if skipped {
+                self.record(snapshot);
+                self.unsynced();
+            }
+            skipped
+        }
+
+        /// Removes the currently selected element, returns true on successful removal.
+        /// Directories are removed while keeping their content
         pub fn remove_element(&mut self) -> bool {
-            if let Some(index) = self.list_state.selected() {
-                match self.elements.get_mut(index) {
-                    Some(element) => {
+            let snapshot: Snapshot = self.snapshot(format!("removal of '{}'", self.selected_title()));
+            let removed: bool = match self.get_selected_element_mut() {
+                Some(element) => match element.id {
+                    Some(id) => self.remove(id),
+                    // Entries that were never synced don't have an ID yet
+                    None => {
                         element.removed = true;
-                        return true;
-                    }
-                    _ => (),
+                        true
+                    },
+                },
+                None => return self.remove_directory(false),
+            };
+            if removed {
+                self.record(snapshot);
+                self.unsynced();
+            }
+            removed
+        }
+
+        /// Removes the currently selected directory, either together with its
+        /// content or by moving the content up into the parent directory.
+        /// Returns true on successful removal
+        pub fn remove_directory(&mut self, with_content: bool) -> bool {
+            let Some(mut path) = self.get_selected_path() else {
+                return false;
+            };
+            let snapshot: Snapshot = self.snapshot(format!("removal of '{}'", self.selected_title()));
+            let Some(directory) = self.registry.node_mut(&path).and_then(|n| n.directory_mut()) else {
+                return false;
+            };
+            directory.removed = true;
+            if with_content {
+                Registry::for_each_node_mut(&mut directory.content, &mut |n| n.remove());
+            } else {
+                let content: Vec<RegistryNode> = directory.content
+                    .drain(..)
+                    .map(|mut n| {
+                        n.set_moved(true);
+                        n
+                    })
+                    .collect();
+                let indx: usize = path.pop().unwrap_or(0);
+                if let Some(parent) = self.registry.content_mut(&path) {
+                    parent.splice(indx+1..indx+1, content);
                 }
             }
-            false
+            self.record(snapshot);
+            self.unsynced();
+            true
         }
 
-        /// Removes the currently selected attribute from the currently selected element
+        /// Removes the currently selected attribute from the currently selected node
         /// returns true on successful removal
         pub fn remove_attribute(&mut self) -> bool {
             if let Some(node) = self.get_selected_attribute() {
-                if let Some(element) = self.get_selected_element_mut() {
-                    match element.nodes().remove(&node.0) {
-                        Some(_) => {
-                            element.modified();
-                            return true;
-                        },
-                        _ => (),
+                let snapshot: Snapshot = self.snapshot(format!("removal of attribute '{}'", node.0));
+                if let Some(selected) = self.get_selected_node_mut() {
+                    if selected.nodes().remove(&node.0).is_some() {
+                        selected.modified();
+                        self.record(snapshot);
+                        self.unsynced();
+                        return true;
                     };
                 }
             }
 
             false
         }
+
+        /// Returns the title of the currently selected node to describe changes
+        /// made to it
+        pub fn selected_title(&self) -> String {
+            self.get_selected_node()
+                .and_then(|n| n.title())
+                .unwrap_or("<no name>".to_string())
+        }
+
+        /// Takes a snapshot of the local registry before it is changed, the
+        /// label describes the change
+        pub fn snapshot(&self, label: String) -> Snapshot {
+            Snapshot {
+                label,
+                placements: self.registry.placements(),
+                synced: self.synced,
+                selected: self.get_selected_path(),
+            }
+        }
+
+        /// Adds the change since the snapshot to the history so it can be
+        /// undone, forgetting the oldest change once the history is full.
+        /// Changes undone before can not be redone anymore afterwards
+        pub fn record(&mut self, snapshot: Snapshot) {
+            let nodes: Vec<NodeChange> = Registry::changes_between(&snapshot.placements, &self.registry.placements());
+            if nodes.is_empty() {
+                return;
+            }
+            self.undo_history.push_back(Change {
+                label: snapshot.label,
+                nodes,
+                synced: snapshot.synced,
+                selected: snapshot.selected,
+                merged: false,
+            });
+            if self.undo_history.len() > crate::UNDO_HISTORY_LIMIT {
+                self.undo_history.pop_front();
+            }
+            self.redo_history.clear();
+        }
+
+        /// Forgets the changes of nodes that merging with the server changed
+        /// or removed, all other changes can still be undone and redone
+        fn forget_remote_changes(&mut self, before: &HashMap<u64, Placement>) {
+            let after: HashMap<u64, Placement> = self.registry.placements();
+            let changed: HashSet<u64> = before
+                .iter()
+                .filter(|(key, b)| after
+                    .get(key)
+                    .is_none_or(|a| a.parent != b.parent || a.node.attributes() != b.node.attributes())
+                )
+                .map(|(key, _)| *key)
+                .collect();
+            let touched = |c: &Change| c.nodes.iter().any(|n| changed.contains(&n.key));
+            self.undo_history.retain(|c| !touched(c));
+            self.redo_history.retain(|c| !touched(c));
+            self.undo_history
+                .iter_mut()
+                .chain(self.redo_history.iter_mut())
+                .for_each(|c| {
+                    c.synced = false;
+                    c.merged = true;
+                });
+        }
+
+        /// Reverts the given change and returns the change that was reverted
+        /// with the same label, to return to it again
+        fn restore(&mut self, change: Change) -> Change {
+            let selected: Option<Vec<usize>> = self.get_selected_path();
+            let placements: HashMap<u64, Placement> = self.registry.placements();
+            self.registry.revert(&change.nodes, change.merged);
+            let left: Change = Change {
+                label: change.label,
+                nodes: Registry::changes_between(&placements, &self.registry.placements()),
+                synced: self.synced,
+                selected,
+                merged: change.merged,
+            };
+            self.synced = change.synced;
+
+            let len: usize = self.visible_paths().len();
+            if self.list_state.selected().is_some_and(|i| i >= len) {
+                self.list_state.select(len.checked_sub(1));
+            }
+            if let Some(path) = change.selected {
+                self.select_path(&path);
+            }
+            left
+        }
+
+        /// Undoes the latest change and returns what it was
+        pub fn undo(&mut self) -> Option<String> {
+            let change: Change = self.undo_history.pop_back()?;
+            let left: Change = self.restore(change);
+            let label: String = left.label.clone();
+            self.redo_history.push(left);
+            Some(label)
+        }
+
+        /// Redoes the latest undone change and returns what it was
+        pub fn redo(&mut self) -> Option<String> {
+            let change: Change = self.redo_history.pop()?;
+            let left: Change = self.restore(change);
+            let label: String = left.label.clone();
+            self.undo_history.push_back(left);
+            Some(label)
+        }
     }
 
     #[derive(PartialEq,)]
@@ -1427,25 +4554,45 @@ pub(crate) mod data_types {
         Clear,      // C
         Edit,       // E
         Fill,       // F
+        Group,      // G
         //Config,     // C
         Help,       // H
+        Merge,      // M
+        Profile,    // P
+        Search,     // /
+        View,       // V
+        Order,      // O
+        Layout,     // L
+        Skip,       // K
+        Done,       // X
+        Undo,       // U
         Quit,       // Q
         Refresh,    // R
         None,
     }
 
-    impl ToString for AppCommand {
-        fn to_string(&self) -> String {
-            match self {
+    impl fmt::Display for AppCommand {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(f, "{}", match self {
                 Self::Clear     => "[c]lear",
                 Self::Fill      => "[f]ill with new",
+                Self::Group     => "[g]roup in new directory",
                 Self::Edit      => "[e]dit",
                 Self::Refresh   => "[r]efresh",
                 //Self::Config    => "[c]onfig",
                 Self::Help      => "[h]elp",
+                Self::Merge     => "[m]erge conflicts",
+                Self::Profile   => "[p]rofile",
+                Self::Search    => "[/]search",
+                Self::View      => "[v]iew",
+                Self::Order     => "[o]rder",
+                Self::Layout    => "[l]ayout",
+                Self::Skip      => "s[k]ip occurrence",
+                Self::Done      => "[x] done",
+                Self::Undo      => "[u]ndo / [U] redo",
                 Self::Quit      => "[q]uit",
                 Self::None      => "[n]one",
-            }.to_string()
+            })
         }
     }
 
@@ -1455,9 +4602,19 @@ pub(crate) mod data_types {
                 0 => Self::Refresh,
                 1 => Self::Edit,
                 2 => Self::Fill,
-                3 => Self::Clear,
-                4 => Self::Help,
-                5 => Self::Quit,
+                3 => Self::Group,
+                4 => Self::Clear,
+                5 => Self::Merge,
+                6 => Self::Search,
+                7 => Self::View,
+                8 => Self::Order,
+                9 => Self::Layout,
+                10 => Self::Done,
+                11 => Self::Skip,
+                12 => Self::Undo,
+                13 => Self::Profile,
+                14 => Self::Help,
+                15 => Self::Quit,
                 _ => Self::None,
             }
         }
@@ -1475,7 +4632,7 @@ pub(crate) mod data_types {
                 result.push(e);
                 i += 1;
             }
-            return result;
+            result
         }
         pub fn get_command_list_string() -> Vec<String> {
             Self::get_command_list().iter().map(|e| e.to_string()).collect()
@@ -1486,7 +4643,17 @@ pub(crate) mod data_types {
                     'c' => Self::Clear,
                     'e' => Self::Edit,
                     'f' => Self::Fill,
+                    'g' => Self::Group,
                     'h' => Self::Help,
+                    'm' => Self::Merge,
+                    'p' => Self::Profile,
+                    '/' => Self::Search,
+                    'v' => Self::View,
+                    'o' => Self::Order,
+                    'l' => Self::Layout,
+                    'k' => Self::Skip,
+                    'x' => Self::Done,
+                    'u' => Self::Undo,
                     'r' => Self::Refresh,
                     'q' => Self::Quit,
                     _ => Self::None,
@@ -1497,7 +4664,7 @@ pub(crate) mod data_types {
         }
     }
 
-    #[derive(Serialize, Deserialize, PartialEq)]
+    #[derive(Serialize, Deserialize, PartialEq, Clone)]
     pub enum AuthMethod {
         Token,
         Password
@@ -1523,64 +4690,1062 @@ pub(crate) mod data_types {
         }
     }
 
-    #[derive(Serialize, Deserialize, PartialEq)]
-    pub struct AppConfig {
+    /// Where the secret of a profile is taken from
+    #[derive(Serialize, Deserialize, PartialEq, Clone, Default)]
+    #[serde(rename_all = "lowercase")]
+    pub enum SecretSource {
+        /// The secret is stored in plain text in the config file itself
+        Config,
+        /// The secret is the value of the named environment variable
+        #[default]
+        Env,
+        /// The secret is the first line printed by the command
+        Command,
+        /// The secret is the content of the file, which must only be
+        /// accessible by its owner
+        File,
+    }
+
+    impl From<usize> for SecretSource {
+        fn from(s: usize) -> SecretSource {
+            match s {
+                1 => SecretSource::Env,
+                2 => SecretSource::Command,
+                3 => SecretSource::File,
+                _ => SecretSource::Config,
+            }
+        }
+    }
+
+    impl SecretSource {
+        /// The source of config files written before the source could be
+        /// chosen, which always stored the secret itself
+        fn legacy() -> Self {
+            SecretSource::Config
+        }
+    }
+
+    impl fmt::Display for SecretSource {
+        fn fmt(&self, f: &mut ::std::fmt::Formatter) -> fmt::Result {
+            let displ: &str = match self {
+                SecretSource::Config => "Config file",
+                SecretSource::Env => "Environment variable",
+                SecretSource::Command => "Command",
+                SecretSource::File => "File",
+            };
+            write!(f, "{}", displ)
+        }
+    }
+
+    /// Where and how to connect to a Freemind server
+    #[derive(Serialize, Deserialize, PartialEq, Clone)]
+    pub struct ServerProfile {
         pub server_address: String,
         pub username: String,
+        /// The secret itself, only used if it is stored in the config file
+        #[serde(default)]
         pub secret: String,
+        /// The variable name, command or file path to obtain the secret from,
+        /// depending on the secret source
+        #[serde(default)]
+        pub secret_ref: String,
+        #[serde(default = "SecretSource::legacy")]
+        pub secret_source: SecretSource,
         pub auth_method: AuthMethod,
     }
 
-    /// Construct a default AppConfig
-    impl ::std::default::Default for AppConfig {
+    /// Construct a default ServerProfile
+    impl ::std::default::Default for ServerProfile {
         fn default() -> Self {
             Self {
                 server_address: "<THE ADDRESS OF THE WEBSERVER>".to_string(),
                 username: "<YOUR USERNAME>".to_string(),
-                secret: "<YOUR TOKEN / SECRET>".to_string(),
+                secret: "".to_string(),
+                secret_ref: "FREEMIND_SECRET".to_string(),
+                secret_source: SecretSource::Env,
                 auth_method: AuthMethod::Token,
             }
         }
     }
 
-    impl fmt::Display for AppConfig {
+    impl fmt::Display for ServerProfile {
         fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
             write!(
                 f,
                 "Server: {}\nUsername: {}\nSecret: {}\nAuth Method: {}",
-                self.server_address, self.username, "*".repeat(self.secret.len()), self.auth_method
+                self.server_address, self.username, self.secret_string(), self.auth_method
             )
         }
     }
 
-    impl AppConfig {
-        /// Returns if the element is the same as the default options
-        pub(crate) fn is_default(&self) -> bool {
-            self == &Self::default()
-        }
-
-        /// Returns if the element is the same as the empty element
-        pub(crate) fn is_empty(&self) -> bool {
-            self == &Self::empty()
-        }
-
+    impl ServerProfile {
         /// Returns a minimal element
         pub(crate) fn empty() -> Self {
             Self {
                 server_address: "".to_string(),
                 username: "".to_string(),
                 secret: "".to_string(),
+                secret_ref: "".to_string(),
+                secret_source: SecretSource::default(),
                 auth_method: AuthMethod::Token,
             }
         }
 
-        pub(crate) fn new(server_address: String, username: String, secret: String, auth_method: AuthMethod) -> Self {
+        pub(crate) fn new(server_address: String, username: String, secret: String, secret_ref: String, secret_source: SecretSource, auth_method: AuthMethod) -> Self {
             Self {
                 server_address,
                 username,
                 secret,
+                secret_ref,
+                secret_source,
                 auth_method,
             }
         }
+
+        /// Returns if the secret is stored in plain text in the config file
+        pub fn stores_secret(&self) -> bool {
+            self.secret_source == SecretSource::Config && !self.secret.is_empty()
+        }
+
+        /// Returns the secret source for display, hiding the secret itself
+        fn secret_string(&self) -> String {
+            match self.secret_source {
+                SecretSource::Config => "*".repeat(self.secret.len()),
+                _ => format!("{} '{}'", self.secret_source, self.secret_ref),
+            }
+        }
+
+        /// Obtains the secret from its source
+        pub fn obtain_secret(&self) -> Result<String, AppError> {
+            match self.secret_source {
+                SecretSource::Config => Ok(self.secret.clone()),
+                SecretSource::Env => std::env::var(&self.secret_ref)
+                    .map_err(|_| AppError::Secret(format!("Environment variable '{}' is not set", self.secret_ref))),
+                SecretSource::Command => data_helpers::run_secret_command(&self.secret_ref)
+                    .map_err(AppError::Secret),
+                SecretSource::File => data_helpers::read_secret_file(&self.secret_ref)
+                    .map_err(AppError::Secret),
+            }
+        }
+    }
+
+    /// A named filter query and sort order
+    #[derive(Serialize, Deserialize, PartialEq, Clone)]
+    pub struct SavedView {
+        pub name: String,
+        /// The query the listed nodes are filtered by
+        #[serde(default)]
+        pub filter: String,
+        /// The attribute the listed nodes are sorted by, descending if
+        /// prefixed with '-'
+        #[serde(default)]
+        pub sort: String,
+    }
+
+    impl SavedView {
+        pub(crate) fn new(name: &str, filter: &str, sort: &str) -> Self {
+            Self {
+                name: name.to_string(),
+                filter: filter.to_string(),
+                sort: sort.to_string(),
+            }
+        }
+    }
+
+    #[derive(Serialize, Deserialize, PartialEq, Clone)]
+    pub struct AppConfig {
+        /// The default profile, stored on the top level of the config file
+        #[serde(flatten)]
+        pub server: ServerProfile,
+        /// Seconds between two syncs in the background, 0 disables them
+        #[serde(default = "AppConfig::default_sync_interval")]
+        pub sync_interval: u64,
+        /// Further named profiles that can be switched to
+        #[serde(default)]
+        pub profiles: BTreeMap<String, ServerProfile>,
+        /// Named filters and sort orders that can be switched between
+        #[serde(default = "AppConfig::default_views")]
+        pub views: Vec<SavedView>,
+        /// The order events are listed in outside of saved views
+        #[serde(default = "AppConfig::default_sort")]
+        pub sort: String,
+        /// Whether the terminal bell rings when an alert fires
+        #[serde(default = "AppConfig::default_alert_bell")]
+        pub alert_bell: bool,
+        /// Minutes a snoozed alert waits before firing again
+        #[serde(default = "AppConfig::default_alert_snooze")]
+        pub alert_snooze: u64,
+        /// Whether completed entries are left out of the lists
+        #[serde(default)]
+        pub hide_done: bool,
+        /// The command the daemon runs for every alert, with the title, the
+        /// due time and the location as arguments and environment variables
+        #[serde(default)]
+        pub alert_hook: String,
+        /// Whether the configuration must not be saved to the file
+        #[serde(skip)]
+        pub read_only: bool,
+        /// Whether to run without a terminal UI, delivering alerts through the hook
+        #[serde(skip)]
+        pub daemon: bool,
+        /// The name of the profile in use, the default profile if none
+        #[serde(skip)]
+        active_profile: Option<String>,
+    }
+
+    /// Construct a default AppConfig
+    impl ::std::default::Default for AppConfig {
+        fn default() -> Self {
+            Self {
+                server: ServerProfile::default(),
+                sync_interval: Self::default_sync_interval(),
+                profiles: BTreeMap::new(),
+                views: Self::default_views(),
+                sort: Self::default_sort(),
+                alert_bell: Self::default_alert_bell(),
+                alert_snooze: Self::default_alert_snooze(),
+                hide_done: false,
+                alert_hook: String::new(),
+                read_only: false,
+                daemon: false,
+                active_profile: None,
+            }
+        }
+    }
+
+    impl fmt::Display for AppConfig {
+        fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            write!(
+                f,
+                "Profile: {}\n{}\nSync Interval: {}s",
+                self.active_profile_name(), self.active(), self.sync_interval
+            )
+        }
+    }
+
+    impl AppConfig {
+        fn default_sync_interval() -> u64 {
+            300
+        }
+
+        fn default_sort() -> String {
+            "due".to_string()
+        }
+
+        fn default_alert_bell() -> bool {
+            true
+        }
+
+        fn default_alert_snooze() -> u64 {
+            5
+        }
+
+        /// Returns the order events are listed in outside of saved views
+        pub fn sort_order(&self) -> SortOrder {
+            SortOrder::parse(&self.sort).unwrap_or(SortOrder::manual())
+        }
+
+        fn default_views() -> Vec<SavedView> {
+            vec![
+                SavedView::new("Today", "due>=\"at 0:00\" due<\"tomorrow at 0:00\"", "due"),
+                SavedView::new("This week", "due>=\"at 0:00\" due<\"in 7 days at 0:00\"", "due"),
+                SavedView::new("Overdue", "due<now", "-due"),
+            ]
+        }
+
+        /// Returns if the profile in use is the same as the default options
+        pub(crate) fn is_default(&self) -> bool {
+            self.active() == &ServerProfile::default()
+        }
+
+        /// Returns if the profile in use is the same as the empty element
+        pub(crate) fn is_empty(&self) -> bool {
+            self.active() == &ServerProfile::empty()
+        }
+
+        /// Returns a minimal element
+        pub(crate) fn empty() -> Self {
+            Self {
+                server: ServerProfile::empty(),
+                ..Self::default()
+            }
+        }
+
+        /// Returns the profile in use
+        pub fn active(&self) -> &ServerProfile {
+            self.active_profile
+                .as_ref()
+                .and_then(|name| self.profiles.get(name))
+                .unwrap_or(&self.server)
+        }
+
+        /// Returns the profile in use as mutable
+        pub fn active_mut(&mut self) -> &mut ServerProfile {
+            match self.active_profile.as_ref().and_then(|name| self.profiles.get_mut(name)) {
+                Some(profile) => profile,
+                None => &mut self.server,
+            }
+        }
+
+        /// Returns the name of the profile in use
+        pub fn active_profile_name(&self) -> &str {
+            self.active_profile.as_deref().unwrap_or(crate::DEFAULT_PROFILE_NAME)
+        }
+
+        /// Returns the names of all profiles, starting with the default profile
+        pub fn profile_names(&self) -> Vec<String> {
+            let mut names: Vec<String> = vec![crate::DEFAULT_PROFILE_NAME.to_string()];
+            names.extend(self.profiles.keys().cloned());
+            names
+        }
+
+        /// Uses the profile of the given name, returns false if there is none
+        pub fn select_profile(&mut self, name: &str) -> bool {
+            if name == crate::DEFAULT_PROFILE_NAME {
+                self.active_profile = None;
+            } else if self.profiles.contains_key(name) {
+                self.active_profile = Some(name.to_string());
+            } else {
+                return false;
+            }
+            true
+        }
+
+        /// Returns the name of the profile following the one in use
+        pub fn next_profile_name(&self) -> String {
+            let names: Vec<String> = self.profile_names();
+            let indx: usize = names
+                .iter()
+                .position(|n| n == self.active_profile_name())
+                .unwrap_or(0);
+            names[(indx + 1) % names.len()].clone()
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::*;
+
+        const REGISTRY: &str = concat!(
+            "<registry>",
+            "<name>Team</name>",
+            "<entry id=\"1\">",
+            "<name>Standup &amp; Review</name>",
+            "<location>Office &lt;3&gt;</location>",
+            "<due>1700000000</due>",
+            "<duration>30</duration>",
+            "<alert>15</alert>",
+            "<color>red</color>",
+            "</entry>",
+            "<directory id=\"2\">",
+            "<name>Projects</name>",
+            "<entry id=\"3\">",
+            "<name>Release</name>",
+            "<notes><link>https://example.com/?a=1&amp;b=2</link><owner>doncato</owner></notes>",
+            "</entry>",
+            "<directory id=\"4\">",
+            "<name>Nested</name>",
+            "<entry id=\"5\"></entry>",
+            "</directory>",
+            "</directory>",
+            "</registry>",
+        );
+
+        #[test]
+        fn round_trip_is_identical() {
+            let registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            assert_eq!(registry.to_xml().unwrap(), REGISTRY);
+        }
+
+        #[test]
+        fn round_trip_of_formatted_document() {
+            let formatted: String = [
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>",
+                "<registry>",
+                "  <entry id=\"7\">",
+                "    <name>Dentist</name>",
+                "    <due>1700000000</due>",
+                "    <empty/>",
+                "  </entry>",
+                "  <directory id=\"8\"/>",
+                "  <entry id=\"9\"/>",
+                "</registry>",
+            ].join("\n");
+            let registry: Registry = Registry::from_string(&formatted).unwrap();
+            let expected: &str = concat!(
+                "<registry>",
+                "<entry id=\"7\"><name>Dentist</name><due>1700000000</due><empty></empty></entry>",
+                "<directory id=\"8\"></directory>",
+                "<entry id=\"9\"></entry>",
+                "</registry>",
+            );
+            assert_eq!(registry.to_xml().unwrap(), expected);
+            assert_eq!(Registry::from_string(expected).unwrap().to_xml().unwrap(), expected);
+        }
+
+        #[test]
+        fn missing_registry_is_an_error() {
+            assert!(Registry::from_string("").is_err());
+            assert!(Registry::from_string("<html><body>Login</body></html>").is_err());
+            assert!(Registry::from_string("<registry/>").is_ok());
+        }
+
+        #[test]
+        fn unsuccessful_status_is_an_error() {
+            assert!(AppError::from_status(200).is_none());
+            assert!(AppError::from_status(204).is_none());
+            assert!(matches!(AppError::from_status(401), Some(AppError::Authentication(401))));
+            assert!(matches!(AppError::from_status(403), Some(AppError::Authentication(403))));
+            assert!(matches!(AppError::from_status(500), Some(AppError::Status(500))));
+        }
+
+        #[test]
+        fn text_is_unescaped() {
+            let registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            assert_eq!(registry.find(1).unwrap().title(), Some("Standup & Review".to_string()));
+            let values: Vec<(String, String)> = registry.find(3).unwrap().get_vecs();
+            assert!(values.contains(&("notes/link".to_string(), "https://example.com/?a=1&b=2".to_string())));
+        }
+
+        #[test]
+        fn removed_and_unidentified_nodes_are_skipped() {
+            let mut registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            registry.find_mut(3).unwrap().remove();
+            registry.find_mut(4).unwrap().remove();
+            registry.push(RegistryNode::Element(EntryNode::new(None, HashMap::new())));
+
+            let xml: String = registry.to_xml().unwrap();
+            let reparsed: Registry = Registry::from_string(&xml).unwrap();
+            let mut ids: Vec<u16> = reparsed.ids();
+            ids.sort();
+            assert_eq!(ids, vec![1, 2]);
+        }
+
+        #[test]
+        fn edited_attributes_are_written() {
+            let mut registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            let node: &mut RegistryNode = registry.find_mut(3).unwrap();
+            data_helpers::insert_chain_to_map(node.nodes(), data_helpers::name_chain("notes/owner"), NodeValue::Text("someone".to_string()));
+            node.nodes().remove(&NodeName::Title);
+
+            let reparsed: Registry = Registry::from_string(&registry.to_xml().unwrap()).unwrap();
+            let node: &RegistryNode = reparsed.find(3).unwrap();
+            assert_eq!(node.title(), None);
+            assert!(node.get_vecs().contains(&("notes/owner".to_string(), "someone".to_string())));
+            assert!(node.get_vecs().contains(&("notes/link".to_string(), "https://example.com/?a=1&b=2".to_string())));
+        }
+
+        #[test]
+        fn cache_keeps_local_changes() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+            state.registry.find_mut(1).unwrap().remove();
+            state.registry.find_mut(3).unwrap().modified();
+            state.registry.push(RegistryNode::Element(EntryNode::new(None, HashMap::from([(NodeName::Title, NodeValue::Text("New".to_string()))]))));
+
+            let mut restored: AppState = AppState::new(AppConfig::empty());
+            restored.load_cache(&state.to_cache().unwrap()).unwrap();
+
+            assert!(!restored.is_synced());
+            assert!(restored.registry.find(1).unwrap().removed());
+            assert!(restored.registry.find(3).unwrap().is_modified());
+            assert!(!restored.registry.find(5).unwrap().is_modified());
+            assert!(restored.registry.top_level().iter().any(|n| n.id().is_none() && n.title() == Some("New".to_string())));
+            assert_eq!(restored.base, state.base);
+            assert_eq!(restored.base_parents, state.base_parents);
+            assert_eq!(restored.to_cache().unwrap(), state.to_cache().unwrap());
+        }
+
+        #[test]
+        fn changes_during_upload_are_kept() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            let node: &mut RegistryNode = state.registry.find_mut(3).unwrap();
+            node.nodes().insert(NodeName::Location, NodeValue::Text("Home".to_string()));
+            node.modified();
+
+            assert!(state.merge_fetched(REGISTRY).unwrap().is_some());
+
+            let node: &mut RegistryNode = state.registry.find_mut(1).unwrap();
+            node.nodes().insert(NodeName::Location, NodeValue::Text("Elsewhere".to_string()));
+            node.modified();
+
+            state.finish_sync();
+
+            assert!(!state.registry.find(3).unwrap().is_modified());
+            assert!(state.registry.find(1).unwrap().is_modified());
+            assert!(!state.is_synced());
+            assert_eq!(state.base[&3].get(&NodeName::Location), Some(&NodeValue::Text("Home".to_string())));
+        }
+
+        #[test]
+        fn search_finds_nested_attributes_in_collapsed_directories() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+
+            "DONCATO".chars().for_each(|c| state.push_search(c));
+            let titles: Vec<Option<String>> = state.get_visible_nodes().iter().map(|(_, n)| n.title()).collect();
+            assert_eq!(titles, vec![Some("Projects".to_string()), Some("Release".to_string())]);
+            assert_eq!(state.get_selected_node().and_then(|n| n.id()), Some(3));
+
+            assert_eq!(data_helpers::find_matches("Owner: DonCato", "doncato"), vec![(7, 14)]);
+
+            state.clear_search();
+            assert_eq!(state.get_visible_nodes().len(), 2);
+        }
+
+        #[test]
+        fn query_compares_attributes() {
+            let registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            let matching = |query: &str| -> Vec<u16> {
+                let query: Query = Query::parse(query).unwrap();
+                registry
+                    .matching_paths(&|n: &RegistryNode| query.matches(n))
+                    .iter()
+                    .filter_map(|p| registry.node(p))
+                    .filter(|n| matches!(n, RegistryNode::Element(_)))
+                    .filter_map(|n| n.id())
+                    .collect()
+            };
+
+            assert_eq!(matching("due<1700000001 location:office"), vec![1]);
+            assert_eq!(matching("due>now"), Vec::<u16>::new());
+            assert_eq!(matching("duration>=30 COLOR=Red has:alert"), vec![1]);
+            assert_eq!(matching("notes/owner=doncato"), vec![3]);
+            assert_eq!(matching("has:notes -title:standup"), vec![3]);
+            assert_eq!(matching("\"standup & review\""), vec![1]);
+
+            assert!(Query::parse("due<").is_err());
+            assert!(Query::parse("due<whenever").is_err());
+            assert!(Query::parse("\"unclosed").is_err());
+        }
+
+        #[test]
+        fn views_filter_and_sort_the_list() {
+            let mut config: AppConfig = AppConfig::empty();
+            config.views = vec![SavedView::new("Named", "has:name", "-name")];
+            let mut state: AppState = AppState::new(config);
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.registry.find_mut(2).unwrap().directory_mut().unwrap().expand();
+
+            assert_eq!(state.select_next_view(), Some("Named".to_string()));
+            let titles: Vec<Option<String>> = state.get_visible_nodes().iter().map(|(_, n)| n.title()).collect();
+            assert_eq!(titles, vec![
+                Some("Standup & Review".to_string()),
+                Some("Projects".to_string()),
+                Some("Release".to_string()),
+                Some("Nested".to_string()),
+            ]);
+
+            state.cycle_sort();
+            assert_ne!(state.get_sort().to_string(), "-name");
+            assert_eq!(state.config().sort, "due");
+            assert_eq!(state.config().views[0].sort, "-name");
+
+            assert_eq!(state.select_next_view(), None);
+            assert_eq!(state.get_sort().to_string(), "due");
+            assert_eq!(state.get_visible_nodes().len(), 4);
+        }
+
+        #[test]
+        fn missing_values_sort_last() {
+            let registry: Registry = Registry::from_string(REGISTRY).unwrap();
+            let ordered = |sort: &str| -> Vec<Option<u16>> {
+                let sort: SortOrder = SortOrder::parse(sort).unwrap();
+                let mut paths: Vec<Vec<usize>> = registry.matching_paths(&|_| true);
+                registry.sort_paths(&mut paths, &|a, b| sort.compare(a, b));
+                paths.iter().filter_map(|p| registry.node(p)).map(|n| n.id()).collect()
+            };
+
+            assert_eq!(ordered("-due"), vec![Some(1), Some(2), Some(3), Some(4), Some(5)]);
+            assert_eq!(ordered("-id"), vec![Some(2), Some(4), Some(5), Some(3), Some(1)]);
+            assert_eq!(ordered("name"), vec![Some(2), Some(4), Some(5), Some(3), Some(1)]);
+            assert_eq!(SortOrder::parse("-manual"), Some(SortOrder::manual()));
+        }
+
+        #[test]
+        fn manual_order_is_synced() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            let content: &mut Vec<RegistryNode> = state.registry.content_mut(&[]).unwrap();
+            content.swap(0, 1);
+            Registry::number_positions(content);
+            assert!(state.registry.has_local_changes());
+
+            let payload: String = state.merge_fetched(REGISTRY).unwrap().unwrap();
+            assert!(payload.find("<directory id=\"2\">").unwrap() < payload.find("<entry id=\"1\">").unwrap());
+            state.finish_sync();
+
+            // Another device moves the entry back to the top
+            let remote: String = payload
+                .replace("<position>0</position>", "<position>2</position>")
+                .replace("<position>1</position>", "<position>0</position>");
+            assert!(state.merge_fetched(&remote).unwrap().is_none());
+            state.finish_sync();
+            let ids: Vec<Option<u16>> = state.registry.top_level().iter().map(|n| n.id()).collect();
+            assert_eq!(ids, vec![Some(1), Some(2)]);
+        }
+
+        #[test]
+        fn moves_are_merged_by_id() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            assert!(state.registry.move_node(4, None));
+            state.registry.find_mut(4).unwrap().set_moved(true);
+
+            // Another device moves the first entry and adds one to the nested directory
+            let mut remote: Registry = Registry::from_string(REGISTRY).unwrap();
+            assert!(remote.move_node(1, Some(2)));
+            remote.push(RegistryNode::Element(EntryNode::new(Some(6), HashMap::new())));
+            assert!(remote.move_node(6, Some(4)));
+
+            let payload: String = state.merge_fetched(&remote.to_xml().unwrap()).unwrap().unwrap();
+            state.finish_sync();
+
+            assert!(state.conflicts.is_empty());
+            assert_eq!(state.registry.ids().iter().filter(|id| **id == 4).count(), 1);
+            let parents: HashMap<u16, Option<u16>> = state.registry.parents();
+            assert_eq!((parents[&1], parents[&4], parents[&6]), (Some(2), None, Some(4)));
+            assert_eq!(Registry::from_string(&payload).unwrap().parents(), parents);
+            assert_eq!(state.base_parents, parents);
+        }
+
+        #[test]
+        fn moves_to_different_directories_conflict() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            assert!(state.registry.move_node(3, None));
+            state.registry.find_mut(3).unwrap().set_moved(true);
+
+            let mut remote: Registry = Registry::from_string(REGISTRY).unwrap();
+            assert!(remote.move_node(3, Some(4)));
+
+            let payload: String = state.merge_fetched(&remote.to_xml().unwrap()).unwrap().unwrap();
+            state.finish_sync();
+
+            assert_eq!(state.conflicts.len(), 1);
+            assert_eq!(state.conflicts[0].parents, Some(ConflictParents { base: Some(2), local: None, remote: Some(4) }));
+            assert_eq!(state.conflicts[0].local, Some(crate::DISPLAY_NESTING_STRING.to_string()));
+            assert_eq!(state.conflicts[0].remote, Some("Nested".to_string()));
+            assert_eq!(Registry::from_string(&payload).unwrap().parents()[&3], Some(4));
+            assert_eq!(state.registry.parents()[&3], None);
+            assert_eq!(state.base_parents[&3], Some(2));
+
+            state.conflict_state.select(Some(0));
+            assert!(state.resolve_conflict(ConflictChoice::Local));
+            assert_eq!(state.registry.parents()[&3], None);
+            assert!(state.registry.find(3).unwrap().is_moved());
+            assert_eq!(state.base_parents[&3], Some(4));
+        }
+
+        #[test]
+        fn local_edits_survive_directories_removed_on_the_server() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            let node: &mut RegistryNode = state.registry.find_mut(5).unwrap();
+            node.nodes().insert(NodeName::Title, NodeValue::Text("Edited".to_string()));
+            node.modified();
+
+            let mut remote: Registry = Registry::from_string(REGISTRY).unwrap();
+            assert!(remote.drop_node(2));
+
+            let payload: String = state.merge_fetched(&remote.to_xml().unwrap()).unwrap().unwrap();
+            state.finish_sync();
+
+            assert!(state.conflicts.is_empty());
+            assert!(state.registry.find(2).is_none());
+            assert!(state.registry.find(3).is_none());
+            assert!(state.registry.find(4).is_none());
+            assert_eq!(state.registry.parents().get(&5), Some(&None));
+            assert_eq!(state.registry.find(5).unwrap().title(), Some("Edited".to_string()));
+            assert!(payload.contains("<entry id=\"5\">"));
+            assert!(!payload.contains("<directory id=\"2\">"));
+        }
+
+        #[test]
+        fn conflicts_survive_a_restart() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+
+            let node: &mut RegistryNode = state.registry.find_mut(3).unwrap();
+            node.nodes().insert(NodeName::Location, NodeValue::Text("Home".to_string()));
+            node.modified();
+            assert!(state.registry.move_node(5, None));
+            state.registry.find_mut(5).unwrap().set_moved(true);
+
+            let mut remote: Registry = Registry::from_string(REGISTRY).unwrap();
+            let remote_node: &mut RegistryNode = remote.find_mut(3).unwrap();
+            remote_node.nodes().insert(NodeName::Location, NodeValue::Text("Office".to_string()));
+            assert!(remote.move_node(5, Some(2)));
+
+            state.merge_fetched(&remote.to_xml().unwrap()).unwrap();
+            state.finish_sync();
+            assert_eq!(state.conflicts.len(), 2);
+
+            let mut restored: AppState = AppState::new(AppConfig::empty());
+            restored.load_cache(&state.to_cache().unwrap()).unwrap();
+
+            assert!(!restored.is_synced());
+            assert_eq!(restored.conflicts, state.conflicts);
+            assert_eq!(restored.base[&3].get(&NodeName::Location), None);
+            assert_eq!(restored.base_parents[&5], Some(4));
+
+            restored.conflict_state.select(Some(0));
+            assert!(restored.resolve_conflict(ConflictChoice::Remote));
+            assert_eq!(restored.registry.find(3).unwrap().attributes().get(&NodeName::Location), Some(&NodeValue::Text("Office".to_string())));
+        }
+
+        #[test]
+        fn new_attributes_are_kept_when_merging() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+            state.sort = SortOrder::manual();
+            state.list_state.select(Some(0));
+
+            state.modification_buffer = Some("room".to_string());
+            state.create_new_attribute_from_edit();
+            assert!(state.registry.find(1).unwrap().is_modified());
+
+            let payload: String = state.merge_fetched(REGISTRY).unwrap().unwrap();
+            state.finish_sync();
+
+            assert!(payload.contains("<room></room>") || payload.contains("<room/>"));
+            assert!(state.registry.find(1).unwrap().attributes().contains_key(&NodeName::from_str("room")));
+            assert!(state.conflicts.is_empty());
+        }
+
+        #[test]
+        fn calendar_lists_entries_on_the_selected_day() {
+            let day: NaiveDate = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+            let late: i64 = data_helpers::local_timestamp(day.pred_opt().unwrap().and_hms_opt(23, 0, 0).unwrap()).unwrap();
+            let early: i64 = data_helpers::local_timestamp(day.and_hms_opt(9, 0, 0).unwrap()).unwrap();
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(&format!(
+                "<registry><entry id=\"1\"><due>{}</due></entry><entry id=\"2\"><due>{}</due><duration>120</duration></entry></registry>",
+                early,
+                late
+            )).unwrap();
+
+            let ids: Vec<Option<u16>> = state.entries_on_day(day).iter().map(|e| e.id).collect();
+            assert_eq!(ids, vec![Some(2), Some(1)]);
+            assert_eq!(state.entries_on_day(day.succ_opt().unwrap()).len(), 0);
+
+            state.cycle_layout();
+            state.calendar_day = day.succ_opt().unwrap();
+            assert_eq!(state.get_visible_nodes().len(), 0);
+            state.create_new_element();
+            let due: u32 = match state.get_selected_node() {
+                Some(RegistryNode::Element(e)) => e.due().unwrap(),
+                _ => panic!("the new entry is not selected"),
+            };
+            assert_eq!(Some(due as i64), data_helpers::local_timestamp(state.calendar_day.and_time(NaiveTime::MIN)));
+            assert_eq!(state.get_visible_nodes().len(), 1);
+        }
+
+        #[test]
+        fn agenda_lists_entries_chronologically() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(concat!(
+                "<registry>",
+                "<entry id=\"1\"><due>1700003600</due></entry>",
+                "<entry id=\"2\"><name>Undated</name></entry>",
+                "<directory id=\"3\"><entry id=\"4\"><due>1700000000</due><duration>30</duration></entry></directory>",
+                "</registry>",
+            )).unwrap();
+            state.layout = AppLayout::Agenda;
+            let ids: Vec<Option<u16>> = state.get_visible_nodes().iter().map(|(_, n)| n.id()).collect();
+            assert_eq!(ids, vec![Some(4), Some(1)]);
+
+            // Everything lies in the past, so the last entry is the closest one
+            state.select_upcoming();
+            assert_eq!(state.list_state.selected(), Some(1));
+
+            let today: NaiveDate = NaiveDate::from_ymd_opt(2026, 10, 16).unwrap();
+            assert_eq!(data_helpers::day_heading(today, today), "Today");
+            assert_eq!(data_helpers::day_heading(today.succ_opt().unwrap(), today), "Tomorrow");
+            assert_eq!(data_helpers::day_heading(NaiveDate::from_ymd_opt(2026, 10, 19).unwrap(), today), "Monday");
+            assert_eq!(data_helpers::day_heading(NaiveDate::from_ymd_opt(2026, 11, 2).unwrap(), today), "Mon, 02 Nov 2026");
+        }
+
+        #[test]
+        fn week_slots_flag_overlapping_entries() {
+            let day: NaiveDate = NaiveDate::from_ymd_opt(2026, 3, 11).unwrap();
+            let at = |hour: u32, minute: u32| data_helpers::local_timestamp(day.and_hms_opt(hour, minute, 0).unwrap()).unwrap();
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(&format!(
+                concat!(
+                    "<registry>",
+                    "<entry id=\"1\"><due>{}</due><duration>60</duration></entry>",
+                    "<entry id=\"2\"><due>{}</due><duration>30</duration></entry>",
+                    "<entry id=\"3\"><due>{}</due></entry>",
+                    "<entry id=\"4\"><due>{}</due></entry>",
+                    "</registry>",
+                ),
+                at(10, 0),
+                at(10, 30),
+                at(12, 0),
+                at(12, 1)
+            )).unwrap();
+
+            let entries: Vec<&EntryNode> = state.entries_on_day(day);
+            assert_eq!(EntryNode::overlapping(&entries, day), vec![true, true, false, false]);
+
+            state.layout = AppLayout::Week;
+            state.calendar_day = day;
+            state.calendar_hour = 14;
+            state.create_new_element();
+            let Some(RegistryNode::Element(e)) = state.get_selected_node() else {
+                panic!("the new entry is not selected");
+            };
+            assert_eq!(e.span().map(|(start, end)| (start.timestamp(), end.timestamp())), Some((at(14, 0), at(15, 0))));
+        }
+
+        #[test]
+        fn dismissed_alerts_do_not_fire_again() {
+            assert!(matches!(AlertRule::parse("2h"), Some(AlertRule::Before(120))));
+            assert!(matches!(AlertRule::parse("0 0 9 * * *"), Some(AlertRule::Schedule(_))));
+            assert!(AlertRule::parse("soon").is_none());
+
+            let due: i64 = Local::now().timestamp() + 10 * 60;
+            let registry: String = format!(
+                concat!(
+                    "<registry>",
+                    "<entry id=\"1\"><name>Standup</name><due>{}</due><alert>15</alert></entry>",
+                    "<entry id=\"2\"><name>Later</name><due>{}</due><alert>5</alert></entry>",
+                    "</registry>",
+                ),
+                due,
+                due
+            );
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(&registry).unwrap();
+
+            assert!(state.check_alerts());
+            assert_eq!(state.get_alert().map(|a| a.title.as_str()), Some("Standup"));
+            assert!(!state.check_alerts());
+            state.dismiss_alert();
+            assert!(state.prompt.is_none());
+            assert!(!state.check_alerts());
+
+            let mut restarted: AppState = AppState::new(AppConfig::empty());
+            restarted.registry = Registry::from_string(&registry).unwrap();
+            restarted.load_dismissed(&state.dismissed_to_string());
+            assert!(!restarted.check_alerts());
+        }
+
+        #[test]
+        fn recurring_entries_expand_into_occurrences() {
+            let local = |y: i32, m: u32, d: u32| NaiveDate::from_ymd_opt(y, m, d).unwrap()
+                .and_hms_opt(9, 0, 0).unwrap()
+                .and_local_timezone(Local).earliest().unwrap();
+            let monthly: Recurrence = Recurrence::parse("Monthly").unwrap();
+            assert_eq!(monthly.next_start(local(2026, 1, 31), local(2026, 2, 1)), Some(local(2026, 2, 28)));
+            assert_eq!(monthly.next_start(local(2026, 1, 31), local(2026, 3, 1)), Some(local(2026, 3, 31)));
+            let cron: Recurrence = Recurrence::parse("0 0 9 * * Mon").unwrap();
+            assert_eq!(cron.next_start(local(2026, 3, 2), local(2026, 3, 3)), Some(local(2026, 3, 9)));
+
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(&format!(
+                "<registry><entry id=\"1\"><due>{}</due><duration>30</duration><repeat>weekly</repeat></entry></registry>",
+                local(2026, 3, 2).timestamp()
+            )).unwrap();
+            assert_eq!(state.entries_on_day(NaiveDate::from_ymd_opt(2026, 3, 16).unwrap()).len(), 1);
+            assert_eq!(state.entries_on_day(NaiveDate::from_ymd_opt(2026, 3, 17).unwrap()).len(), 0);
+            let occurrences: Vec<(DateTime<Local>, DateTime<Local>)> = state.registry.entries()[0].occurrences(local(2026, 3, 3), local(2026, 3, 31));
+            assert_eq!(occurrences.len(), 4);
+            assert_eq!(occurrences[0].1 - occurrences[0].0, chrono::Duration::minutes(30));
+
+            state.list_state.select(Some(0));
+            assert!(state.skip_occurrence());
+            assert_eq!(state.registry.entries()[0].due(), Some(local(2026, 3, 9).timestamp() as u32));
+            assert!(!state.is_synced());
+        }
+
+        #[test]
+        fn completed_entries_are_marked_and_can_be_hidden() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(concat!(
+                "<registry>",
+                "<entry id=\"1\"><name>Task</name></entry>",
+                "<entry id=\"2\"><name>Chore</name><due>1700000000</due><repeat>daily</repeat></entry>",
+                "</registry>",
+            )).unwrap();
+            state.sort = SortOrder::manual();
+
+            state.list_state.select(Some(0));
+            assert_eq!(state.toggle_done(), Some(Completion::Done));
+            assert!(state.registry.to_xml().unwrap().contains("<done>"));
+
+            state.list_state.select(Some(1));
+            assert_eq!(state.toggle_done(), Some(Completion::Advanced));
+            assert!(!state.registry.entries()[1].is_done());
+            assert_eq!(state.registry.entries()[1].due(), Some(1700000000 + 24 * 60 * 60));
+
+            assert!(state.toggle_hide_done());
+            let titles: Vec<Option<String>> = state.get_visible_nodes().iter().map(|(_, n)| n.title()).collect();
+            assert_eq!(titles, vec![Some("Chore".to_string())]);
+
+            state.toggle_hide_done();
+            state.list_state.select(Some(0));
+            assert_eq!(state.toggle_done(), Some(Completion::Undone));
+            assert!(!state.registry.to_xml().unwrap().contains("<done>"));
+        }
+
+        #[test]
+        fn removals_can_be_undone_and_redone() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(concat!(
+                "<registry>",
+                "<entry id=\"1\"><name>Meeting</name><location>Office</location></entry>",
+                "<entry id=\"2\"><name>Lunch</name></entry>",
+                "</registry>",
+            )).unwrap();
+            state.sort = SortOrder::manual();
+            state.synced = true;
+            assert_eq!(state.undo(), None);
+
+            state.list_state.select(Some(0));
+            state.details_state.select(Some(1));
+            assert_eq!(state.get_selected_attribute().map(|a| a.0), Some(NodeName::Location));
+            assert!(state.remove_attribute());
+            assert!(!state.registry.to_xml().unwrap().contains("Office"));
+
+            assert_eq!(state.undo(), Some("removal of attribute 'location'".to_string()));
+            assert!(state.registry.to_xml().unwrap().contains("Office"));
+            assert!(state.synced);
+            assert_eq!(state.redo(), Some("removal of attribute 'location'".to_string()));
+            assert!(!state.registry.to_xml().unwrap().contains("Office"));
+            assert!(!state.synced);
+
+            state.undo();
+            state.list_state.select(Some(1));
+            assert!(state.remove_element());
+            assert_eq!(state.redo(), None);
+            assert_eq!(state.undo(), Some("removal of 'Lunch'".to_string()));
+            assert!(state.registry.entries().iter().all(|e| !e.removed));
+            assert_eq!(state.selected_title(), "Lunch");
+        }
+
+        #[test]
+        fn undo_history_survives_syncs() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+            state.sort = SortOrder::manual();
+            state.synced = true;
+            state.list_state.select(Some(0));
+            assert_eq!(state.selected_title(), "Standup & Review");
+            assert!(state.remove_element());
+
+            let payload: String = state.merge_fetched(REGISTRY).unwrap().unwrap();
+            assert!(!payload.contains("Standup"));
+            state.finish_sync();
+            assert!(state.registry.find(1).is_none());
+
+            assert_eq!(state.undo(), Some("removal of 'Standup & Review'".to_string()));
+            assert!(!state.synced);
+            let entry: &RegistryNode = state.registry.find(1).unwrap();
+            assert!(!entry.removed());
+            assert!(entry.is_modified());
+            let remote: String = Registry::from_string(&payload).unwrap().to_xml().unwrap();
+            let payload: String = state.merge_fetched(&remote).unwrap().unwrap();
+            assert!(payload.contains("Standup &amp; Review"));
+        }
+
+        #[test]
+        fn undo_forgets_changes_of_nodes_changed_on_the_server() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.update_base(&state.registry.clone());
+            state.sort = SortOrder::manual();
+            state.list_state.select(Some(0));
+            assert!(state.toggle_done().is_some());
+            state.list_state.select(Some(1));
+            assert_eq!(state.selected_title(), "Projects");
+            assert!(state.remove_directory(true));
+            state.undo();
+
+            let remote: String = REGISTRY.replace("<name>Projects</name>", "<name>Archive</name>");
+            state.merge_fetched(&remote).unwrap();
+            state.finish_sync();
+
+            assert_eq!(state.redo(), None);
+            assert!(state.undo().is_some());
+            assert!(state.registry.find(1).unwrap().attributes().get(&NodeName::Done).is_none());
+            assert_eq!(state.registry.find(2).unwrap().title(), Some("Archive".to_string()));
+        }
+
+        #[test]
+        fn undo_keeps_expanded_directories() {
+            let mut state: AppState = AppState::new(AppConfig::empty());
+            state.registry = Registry::from_string(REGISTRY).unwrap();
+            state.sort = SortOrder::manual();
+            state.list_state.select(Some(0));
+            assert_eq!(state.selected_title(), "Standup & Review");
+            assert!(state.toggle_done().is_some());
+
+            state.registry.find_mut(2).unwrap().directory_mut().unwrap().toggle_expanded();
+            assert!(state.undo().is_some());
+            assert!(state.registry.find(1).unwrap().attributes().get(&NodeName::Done).is_none());
+            assert!(state.registry.find(2).unwrap().directory().unwrap().expanded);
+
+            state.registry.find_mut(2).unwrap().directory_mut().unwrap().toggle_expanded();
+            assert!(state.redo().is_some());
+            assert!(state.registry.find(1).unwrap().attributes().get(&NodeName::Done).is_some());
+            assert!(!state.registry.find(2).unwrap().directory().unwrap().expanded);
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn alert_hook_gets_arguments_and_environment() {
+            let path: std::path::PathBuf = std::env::temp_dir().join(format!("freemind-hook-{}", std::process::id()));
+            let hook: String = format!("printf '%s|%s|%s' \"$1\" \"$3\" \"$FREEMIND_TITLE\" > '{}'", path.display());
+            data_helpers::run_hook(&hook, &["Standup", "", "Office"], &[("FREEMIND_TITLE", "Standup")]).unwrap();
+            assert_eq!(std::fs::read_to_string(&path).unwrap(), "Standup|Office|Standup");
+            assert!(data_helpers::run_hook("exit 3", &[], &[]).is_err());
+            std::fs::remove_file(&path).ok();
+        }
+
+        #[cfg(unix)]
+        #[test]
+        fn secret_file_must_be_private() {
+            use std::os::unix::fs::PermissionsExt;
+            let path: std::path::PathBuf = std::env::temp_dir().join(format!("freemind-secret-{}", std::process::id()));
+            std::fs::write(&path, "hunter2\n").unwrap();
+            let profile: ServerProfile = ServerProfile::new(
+                "".to_string(),
+                "".to_string(),
+                "".to_string(),
+                path.to_string_lossy().to_string(),
+                SecretSource::File,
+                AuthMethod::Token
+            );
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+            assert!(matches!(profile.obtain_secret(), Err(AppError::Secret(_))));
+
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+            assert_eq!(profile.obtain_secret().unwrap(), "hunter2");
+
+            std::fs::remove_file(&path).ok();
+            data_helpers::write_secret_file(&path.to_string_lossy(), "hunter3").unwrap();
+            assert!(data_helpers::write_secret_file(&path.to_string_lossy(), "hunter4").is_err());
+            assert_eq!(profile.obtain_secret().unwrap(), "hunter3");
+
+            std::fs::remove_file(&path).ok();
+        }
     }
-}
\ No newline at end of file
+}
@@ -2,15 +2,16 @@ mod app;
 use crate::app::{engine, ui};
 
 mod data;
-use crate::data::data_types::{AppState, AppConfig, AppCommand};
+use crate::data::data_types::{AppState, AppConfig, AppCommand, Completion, ConflictChoice};
 
 use crossterm::{
     event::{self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, KeyModifiers},
     execute,
+    style::Print,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
-use data::data_types::AppFocus;
-use std::io;
+use data::data_types::{AppFocus, AppLayout};
+use std::{io, time::{Duration, Instant}};
 use tui::{
     backend::{Backend, CrosstermBackend},
     Terminal, 
@@ -20,9 +21,54 @@ const INPUT_CMDLET_PREFIX: &str = "@";
 
 const DISPLAY_NESTING_STRING: &str = "/";
 
-const INFO_MSG_SYNC_FAIL: &str = "Catastrophic failure occured while syncing!";
+const DISPLAY_INDENT_STRING: &str = "  ";
+
+/// The attribute shown for conflicts about the directory a node is located in
+const CONFLICT_DIRECTORY_ATTRIBUTE: &str = "<directory>";
+
+const INPUT_POLL_RATE: Duration = Duration::from_millis(100);
+
+const ALERT_CHECK_RATE: Duration = Duration::from_secs(1);
+
+/// Minutes an alert stays pending after it fired
+const ALERT_WINDOW_MINUTES: i64 = 60;
+
+/// Days dismissed alerts are remembered for
+const ALERT_KEEP_DAYS: i64 = 30;
+
+/// The most occurrences of a recurring entry that are listed at once
+const RECURRENCE_MAX_OCCURRENCES: usize = 500;
+
+/// Days the agenda lists occurrences of recurring entries for
+const AGENDA_RECURRENCE_DAYS: i64 = 28;
+
+/// The most changes that can be undone
+const UNDO_HISTORY_LIMIT: usize = 100;
+
+const DEFAULT_PROFILE_NAME: &str = "default";
+
+/// The name of the offline cache of the TUI
+const REGISTRY_CACHE_NAME: &str = "registry";
+/// The name of the offline cache of the daemon, kept apart from the TUI's one
+const DAEMON_CACHE_NAME: &str = "daemon-registry";
+
+/// Printed whenever a profile keeps its secret in plain text in the config file
+const WARN_MSG_PLAINTEXT_SECRET: &str = "WARNING! The secret is stored in plain text in the config file!\nRun with `--config` to obtain it from an environment variable, a command or a file instead.";
+
+const INFO_MSG_SYNC_FAIL: &str = "Sync failed!";
 const INFO_MSG_EDIT_EMPTY: &str = "Cannot edit empty set!";
 const INFO_MSG_INVALID_CMDLET: &str = "Cannot parse cmdlet!";
+const INFO_MSG_CONFLICTS: &str = "Conflicting changes! Press [m] to merge";
+const INFO_MSG_NO_CONFLICTS: &str = "No conflicts to merge";
+const INFO_MSG_SYNC_CANCELLED: &str = "Sync cancelled";
+const INFO_MSG_NO_PROFILES: &str = "No other profiles configured";
+const INFO_MSG_NO_MATCHES: &str = "No matching events";
+const INFO_MSG_NOT_MANUAL: &str = "Switch to the manual order with [o] to reorder";
+const INFO_MSG_NO_DIRECTION: &str = "The manual order has no direction";
+const INFO_MSG_NOT_RECURRING: &str = "The selected event does not repeat";
+const INFO_MSG_NO_ENTRY: &str = "No event selected";
+const INFO_MSG_NOTHING_TO_UNDO: &str = "Nothing to undo";
+const INFO_MSG_NOTHING_TO_REDO: &str = "Nothing to redo";
 
 /*
 fn set_up_ui<B: Backend>(f: &mut Frame<B>) {
@@ -46,11 +92,11 @@ fn set_up_ui<B: Backend>(f: &mut Frame<B>) {
 
 
 fn select_prev_element(state: &mut AppState) {
-    let len: usize = state.get_elements().len();
+    let len: usize = state.get_visible_nodes().len();
     if len != 0 {
         let max: usize = len-1;
         let mut a: usize = state.list_state.selected().unwrap_or(1);
-        if a <= 0 {
+        if a == 0 {
             a = max
         } else {
             a -= 1
@@ -60,7 +106,7 @@ fn select_prev_element(state: &mut AppState) {
 }
 
 fn select_next_element(state: &mut AppState) {
-    let len: usize = state.get_elements().len();
+    let len: usize = state.get_visible_nodes().len();
     if len != 0 {
         let max: usize = len-1;
         let mut a: usize = state.list_state.selected().unwrap_or(max);
@@ -74,49 +120,169 @@ fn select_next_element(state: &mut AppState) {
 }
 
 fn select_prev_field(state: &mut AppState) {
-    let len: usize = match state.get_selected_element() {
+    let len: usize = match state.get_selected_node() {
         Some(e) => e.flattened_node_count(),
         _ => 0
     };
-    let max: usize = len-1;
-    let mut a: usize = state.details_state.selected().unwrap_or(1);
-    if a <= 0 {
-        a = max
-    } else {
-        a -= 1
+    if len != 0 {
+        let max: usize = len-1;
+        let mut a: usize = state.details_state.selected().unwrap_or(1);
+        if a == 0 {
+            a = max
+        } else {
+            a -= 1
+        }
+        state.details_state.select(Some(a));
     }
-    state.details_state.select(Some(a));
 }
 
 fn select_next_field(state: &mut AppState) {
-    let len: usize = match state.get_selected_element() {
+    let len: usize = match state.get_selected_node() {
         Some(e) => e.flattened_node_count(),
         _ => 0
     };
-    let max: usize = len-1;
-    let mut a: usize = state.details_state.selected().unwrap_or(max);
-    if a >= max {
-        a = 0
+    if len != 0 {
+        let max: usize = len-1;
+        let mut a: usize = state.details_state.selected().unwrap_or(max);
+        if a >= max {
+            a = 0
+        } else {
+            a += 1
+        }
+        state.details_state.select(Some(a));
+    }
+}
+
+fn select_prev_conflict(state: &mut AppState) {
+    let len: usize = state.get_conflicts().len();
+    if len != 0 {
+        let max: usize = len-1;
+        let mut a: usize = state.conflict_state.selected().unwrap_or(1);
+        if a == 0 {
+            a = max
+        } else {
+            a -= 1
+        }
+        state.conflict_state.select(Some(a));
+    }
+}
+
+fn select_next_conflict(state: &mut AppState) {
+    let len: usize = state.get_conflicts().len();
+    if len != 0 {
+        let max: usize = len-1;
+        let mut a: usize = state.conflict_state.selected().unwrap_or(max);
+        if a >= max {
+            a = 0
+        } else {
+            a += 1
+        }
+        state.conflict_state.select(Some(a));
+    }
+}
+
+/// Enters the editing mode for the currently selected attribute
+fn edit_selected_attribute(state: &mut AppState) {
+    if state.set_edit(ui::get_selected_value(state)) {
+        state.focused_on = AppFocus::Edit;
     } else {
-        a += 1
+        state.message = Some(INFO_MSG_EDIT_EMPTY.to_string())
     }
-    state.details_state.select(Some(a));
 }
 
+/// Selects the next or previous node matching the search in the list
+fn jump_to_match(state: &mut AppState, forward: bool) {
+    if state.select_match(forward) {
+        state.focused_on = AppFocus::Elements;
+    } else {
+        state.message = Some(INFO_MSG_NO_MATCHES.to_string());
+    }
+}
+
+/// Redoes the latest undone change and shows what it was
+fn redo(state: &mut AppState) {
+    state.message = match state.redo() {
+        Some(change) => Some(format!("Redid {}", change)),
+        None => Some(INFO_MSG_NOTHING_TO_REDO.to_string()),
+    };
+}
+
+/// Remembers the order the events are sorted in, unless a saved view is in
+/// use, and shows it
+fn sorted(state: &mut AppState) {
+    let order: String = format!("Order: {}", state.get_sort());
+    state.message = match state.get_view() {
+        // The order of a saved view is not saved
+        Some(_) => Some(order),
+        None => match engine::save_app_config(state.config()) {
+            Ok(_) => Some(order),
+            Err(e) => Some(e.to_string()),
+        },
+    };
+}
 
-async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig) -> io::Result<()> {
+/// Starts a sync in the background, showing why if that is not possible
+fn request_sync(state: &mut AppState) {
+    if let Err(e) = engine::start_sync(state) {
+        state.message = Some(format!("{} {}", INFO_MSG_SYNC_FAIL, e));
+    }
+}
+
+async fn run_app<B: Backend>(terminal: &mut Terminal<B>, cfg: AppConfig) -> io::Result<()> {
     let mut state: AppState = AppState::new(cfg);
+    engine::load_cache(&mut state, REGISTRY_CACHE_NAME);
+    engine::load_alerts(&mut state);
+    if !state.get_conflicts().is_empty() {
+        state.message = Some(INFO_MSG_CONFLICTS.to_string());
+    }
     //let mut last_result: Option<reqwest::Error> = None;
 
 
     let view = ui::ui;
 
-    'main: loop {
+    request_sync(&mut state);
+    let mut last_sync: Instant = Instant::now();
+    let mut last_alert_check: Instant = Instant::now() - ALERT_CHECK_RATE;
+
+    let result: io::Result<()> = 'main: loop {
         terminal.draw(|f| view(f, &mut state))?;
         if state.prompt.is_some() {
             terminal.draw(|f| ui::prompt_ui(f, &mut state))?;
         }
 
+        // Advance a running sync
+        if let Some(result) = engine::poll_sync(&mut state).await {
+            state.message = match result {
+                Err(e) => Some(format!("{} {}", INFO_MSG_SYNC_FAIL, e)),
+                Ok(_) if !state.get_conflicts().is_empty() => Some(INFO_MSG_CONFLICTS.to_string()),
+                Ok(_) => None,
+            };
+            engine::write_cache(&state, REGISTRY_CACHE_NAME);
+        }
+
+        // Sync in the background, unless the user is in the middle of something
+        let sync_due: bool = state
+            .sync_interval()
+            .is_some_and(|interval| last_sync.elapsed() >= interval);
+        if sync_due && !state.is_editing() && state.focused_on != AppFocus::Conflicts {
+            request_sync(&mut state);
+            last_sync = Instant::now();
+        }
+
+        // Show alerts that are due
+        if last_alert_check.elapsed() >= ALERT_CHECK_RATE {
+            last_alert_check = Instant::now();
+            engine::load_alerts(&mut state);
+            if state.check_alerts() && state.config().alert_bell {
+                execute!(io::stdout(), Print('\u{7}'))?;
+            }
+        }
+
+        // Wait for input while keeping the timers running
+        if !event::poll(INPUT_POLL_RATE)? {
+            continue;
+        }
+
         // Match Keyboard Events
         if let Event::Key(key) = event::read()? {
             // Ignore if no key was pressed
@@ -133,6 +299,21 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                     KeyCode::Char('c') | KeyCode::Char('q') => {
                         break 'main Ok(());
                     }
+                    KeyCode::Char('r') if !state.is_editing() => {
+                        redo(&mut state);
+                    }
+                    _ => (),
+                }
+            } else if state.get_alert().is_some() { // If an alert is shown
+                match key.code {
+                    KeyCode::Char('z') | KeyCode::Char('Z') => {
+                        state.snooze_alert();
+                        state.message = Some(format!("Alert snoozed for {} minutes", state.config().alert_snooze));
+                    },
+                    KeyCode::Char('d') | KeyCode::Char('D') | KeyCode::Enter | KeyCode::Esc => {
+                        state.dismiss_alert();
+                        engine::write_alerts(&mut state);
+                    },
                     _ => (),
                 }
             } else if state.is_editing() { // If we currently edit something we need to pass the chars:
@@ -142,11 +323,11 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                         state.abort_editing();
                     },
                     KeyCode::Enter => {
-                        if state.focused_on == AppFocus::Edit {
+                        if state.focused_on.edit() {
                             // If we currently edit the value
                             state.focused_on = AppFocus::Attributes;
                             if state.save_changes().is_err() {
-                                state.message = Some(INFO_MSG_INVALID_CMDLET);
+                                state.message = Some(INFO_MSG_INVALID_CMDLET.to_string());
                             };
                         } else {
                             // If we want to create a new attribute
@@ -161,22 +342,105 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                     },
                     _ => (),
                 }
-            } else if key.modifiers == KeyModifiers::SHIFT {
-                match state.focused_on {
-                    AppFocus::Elements => {
-                        match key.code {
-                            KeyCode::Up | KeyCode::Char('W') => {
-                                engine::switch_up(&mut state);
-                                select_prev_element(&mut state);
-                            },
-                            KeyCode::Down | KeyCode::Char('S') => {
-                                engine::switch_down(&mut state);
-                                select_next_element(&mut state);
-                            },
-                            _ => (),
+            } else if state.focused_on == AppFocus::Search { // If we currently type a search
+                match key.code {
+                    KeyCode::Esc => {
+                        state.clear_search();
+                        state.focused_on = AppFocus::Elements;
+                    },
+                    KeyCode::Enter => {
+                        if state.get_search().is_none() {
+                            state.clear_search();
                         }
+                        state.focused_on = AppFocus::Elements;
+                    },
+                    KeyCode::Backspace => {
+                        state.pop_search();
+                    },
+                    KeyCode::Char(c) => {
+                        state.push_search(c);
+                    },
+                    _ => (),
+                }
+            } else if state.focused_on == AppFocus::Conflicts { // If we currently resolve conflicts
+                match key.code {
+                    KeyCode::Esc | KeyCode::Char('q') => {
+                        state.focused_on = AppFocus::Elements;
+                    },
+                    KeyCode::Up | KeyCode::Char('w') => {
+                        select_prev_conflict(&mut state);
                     },
-                    _ => ()
+                    KeyCode::Down | KeyCode::Char('s') => {
+                        select_next_conflict(&mut state);
+                    },
+                    KeyCode::Left | KeyCode::Char('a') => {
+                        state.resolve_conflict(ConflictChoice::Local);
+                    },
+                    KeyCode::Right | KeyCode::Char('d') => {
+                        state.resolve_conflict(ConflictChoice::Remote);
+                    },
+                    KeyCode::Char('b') => {
+                        state.resolve_conflict(ConflictChoice::Base);
+                    },
+                    _ => (),
+                }
+            } else if key.modifiers == KeyModifiers::SHIFT {
+                if key.code == KeyCode::Char('N') {
+                    jump_to_match(&mut state, false);
+                } else if key.code == KeyCode::Char('U') {
+                    redo(&mut state);
+                } else if key.code == KeyCode::Char('X') {
+                    let hidden: bool = state.toggle_hide_done();
+                    state.message = match engine::save_app_config(state.config()) {
+                        Ok(_) if hidden => Some("Completed events are hidden".to_string()),
+                        Ok(_) => Some("Completed events are shown".to_string()),
+                        Err(e) => Some(e.to_string()),
+                    };
+                } else if key.code == KeyCode::Char('O') {
+                    if state.toggle_sort_direction() {
+                        sorted(&mut state);
+                    } else {
+                        state.message = Some(INFO_MSG_NO_DIRECTION.to_string());
+                    }
+                } else if state.focused_on == AppFocus::Calendar {
+                    let week: bool = state.get_layout() == AppLayout::Week;
+                    match key.code {
+                        KeyCode::Left | KeyCode::Char('A') if week => {
+                            state.move_calendar_day(-7);
+                        },
+                        KeyCode::Right | KeyCode::Char('D') if week => {
+                            state.move_calendar_day(7);
+                        },
+                        KeyCode::Left | KeyCode::Char('A') => {
+                            state.move_calendar_month(-1);
+                        },
+                        KeyCode::Right | KeyCode::Char('D') => {
+                            state.move_calendar_month(1);
+                        },
+                        _ => (),
+                    }
+                } else if state.focused_on.elements() {
+                    match key.code {
+                        KeyCode::Up | KeyCode::Char('W') | KeyCode::Down | KeyCode::Char('S') if !state.get_sort().is_manual() => {
+                            state.message = Some(INFO_MSG_NOT_MANUAL.to_string());
+                        },
+                        KeyCode::Up | KeyCode::Char('W') => {
+                            engine::switch_up(&mut state);
+                        },
+                        KeyCode::Down | KeyCode::Char('S') => {
+                            engine::switch_down(&mut state);
+                        },
+                        KeyCode::Left | KeyCode::Char('A') => {
+                            engine::move_out(&mut state);
+                        },
+                        KeyCode::Right | KeyCode::Char('D') => {
+                            engine::move_in(&mut state);
+                        },
+                        KeyCode::Char('C') => {
+                            state.remove_directory(true);
+                        },
+                        _ => (),
+                    }
                 }
             } else {
                 let command: AppCommand = AppCommand::from_key(key.code);
@@ -184,13 +448,67 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                 // Match all keys controlling Commands functionality
                 match command {
                     AppCommand::Refresh => {
-                        if state.sync().await.is_err() {
-                            state.message = Some(INFO_MSG_SYNC_FAIL);
+                        request_sync(&mut state);
+                        last_sync = Instant::now();
+                    }
+                    AppCommand::Merge => {
+                        if state.get_conflicts().is_empty() {
+                            state.message = Some(INFO_MSG_NO_CONFLICTS.to_string());
+                        } else {
+                            state.focused_on = AppFocus::Conflicts;
+                        }
+                    }
+                    AppCommand::Search => {
+                        state.start_search();
+                    }
+                    AppCommand::View => {
+                        let view: String = state.select_next_view().unwrap_or("All".to_string());
+                        state.message = Some(format!("View: {}", view));
+                    }
+                    AppCommand::Order => {
+                        if !state.focused_on.attributes() || !state.sort_by_selected_attribute() {
+                            state.cycle_sort();
+                        }
+                        sorted(&mut state);
+                    }
+                    AppCommand::Layout => {
+                        state.cycle_layout();
+                    }
+                    AppCommand::Done => {
+                        state.message = match state.toggle_done() {
+                            Some(Completion::Done) => Some("Marked as done".to_string()),
+                            Some(Completion::Undone) => Some("Marked as not done".to_string()),
+                            Some(Completion::Advanced) => Some("Moved on to the next occurrence".to_string()),
+                            None => Some(INFO_MSG_NO_ENTRY.to_string()),
+                        };
+                    }
+                    AppCommand::Skip => {
+                        state.message = match state.skip_occurrence() {
+                            true => None,
+                            false => Some(INFO_MSG_NOT_RECURRING.to_string()),
                         };
                     }
+                    AppCommand::Undo => {
+                        state.message = match state.undo() {
+                            Some(change) => Some(format!("Undid {}", change)),
+                            None => Some(INFO_MSG_NOTHING_TO_UNDO.to_string()),
+                        };
+                    }
+                    AppCommand::Profile => {
+                        if state.config().profiles.is_empty() {
+                            state.message = Some(INFO_MSG_NO_PROFILES.to_string());
+                        } else {
+                            let profile: String = engine::switch_profile(&mut state);
+                            request_sync(&mut state);
+                            last_sync = Instant::now();
+                            if state.message.is_none() {
+                                state.message = Some(format!("Switched to profile '{}'", profile));
+                            }
+                        }
+                    }
                     AppCommand::Fill => {
                         match state.focused_on {
-                            AppFocus::Elements => {
+                            AppFocus::Elements | AppFocus::Calendar => {
                                 state.create_new_element();
                                 state.focused_on = AppFocus::Attributes;
                                 state.unsynced();
@@ -201,31 +519,27 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                             _ => ()
                         }
                     }
+                    AppCommand::Group => {
+                        state.create_new_directory();
+                        state.focused_on = AppFocus::Attributes;
+                    }
                     AppCommand::Clear => {
                         match state.focused_on {
                             AppFocus::Elements => {
-                                if state.remove_element() {
-                                    state.unsynced();
-                                };
+                                state.remove_element();
                             },
                             AppFocus::Attributes => {
-                                if state.remove_attribute() {
-                                    state.unsynced();
-                                };
+                                state.remove_attribute();
                             },
                             _ => ()
                         }
                     }
                     AppCommand::Edit => {
-                        if state.set_edit(ui::get_selected_value(&state)) {
-                            state.focused_on = AppFocus::Edit;
-                        } else {
-                            state.message = Some(INFO_MSG_EDIT_EMPTY)
-                        }
+                        edit_selected_attribute(&mut state);
                     }
                     AppCommand::Quit => {
                         if state.is_synced() || state.prompt.is_some() {
-                            return Ok(())
+                            break 'main Ok(())
                         } else {
                             state.prompt = Some("You have unsynced changes!\nDo you really want to exit?".to_string());
                         }
@@ -238,8 +552,14 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                         if state.prompt.is_some() {
                             state.prompt = None;
                         }
+                        else if state.get_sync_phase().is_some() {
+                            state.cancel_sync();
+                            state.message = Some(INFO_MSG_SYNC_CANCELLED.to_string());
+                        }
                         else if state.details_state.selected().is_some() {
                             state.details_state.select(None);
+                        } else if state.get_search().is_some() {
+                            state.clear_search();
                         } else {
                             state.list_state.select(None)
                         }
@@ -250,31 +570,39 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                         } else {
                             match state.focused_on {
                                 AppFocus::Elements => {
-                                    state.focused_on = AppFocus::Attributes;
+                                    let toggled: bool = state.toggle_selected_directory();
+                                    if !toggled {
+                                        state.focused_on = AppFocus::Attributes;
+                                    }
                                 },
                                 AppFocus::Attributes => {
-                                    if state.set_edit(ui::get_selected_value(&state)) {
-                                        state.focused_on = AppFocus::Edit;
-                                    } else {
-                                        state.message = Some(INFO_MSG_EDIT_EMPTY)
-                                    }
+                                    edit_selected_attribute(&mut state);
+                                },
+                                AppFocus::Calendar => {
+                                    state.focused_on = AppFocus::Elements;
+                                    select_next_element(&mut state);
                                 },
                                 _ => {}
                             }
                         }
                     }
+                    KeyCode::Char('n') => {
+                        jump_to_match(&mut state, true);
+                    },
                     KeyCode::Up | KeyCode::Char('w') => {
-                        if state.focused_on.elements() {
-                            select_prev_element(&mut state);
-                        } else {
-                            select_prev_field(&mut state);
+                        match state.focused_on {
+                            AppFocus::Calendar if state.get_layout() == AppLayout::Week => state.move_calendar_hour(-1),
+                            AppFocus::Calendar => state.move_calendar_day(-7),
+                            AppFocus::Elements => select_prev_element(&mut state),
+                            _ => select_prev_field(&mut state),
                         }
                     },
                     KeyCode::Down | KeyCode::Char('s') => {
-                        if state.focused_on.elements() {
-                            select_next_element(&mut state);
-                        } else {
-                            select_next_field(&mut state);
+                        match state.focused_on {
+                            AppFocus::Calendar if state.get_layout() == AppLayout::Week => state.move_calendar_hour(1),
+                            AppFocus::Calendar => state.move_calendar_day(7),
+                            AppFocus::Elements => select_next_element(&mut state),
+                            _ => select_next_field(&mut state),
                         }
                     },
                     KeyCode::Left | KeyCode::Char('a') => {
@@ -285,20 +613,26 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                             AppFocus::Attributes => {
                                 state.focused_on = AppFocus::Elements
                             },
+                            AppFocus::Elements if matches!(state.get_layout(), AppLayout::Calendar | AppLayout::Week) => {
+                                state.focused_on = AppFocus::Calendar;
+                                state.list_state.select(None);
+                            },
+                            AppFocus::Calendar => {
+                                state.move_calendar_day(-1);
+                            },
                             _ => {}
                         }
                     }
                     KeyCode::Right | KeyCode::Char('d') => {
                         match state.focused_on {
+                            AppFocus::Calendar => {
+                                state.move_calendar_day(1);
+                            },
                             AppFocus::Elements => {
                                 state.focused_on = AppFocus::Attributes;
                             },
                             AppFocus::Attributes => {
-                                if state.set_edit(ui::get_selected_value(&state)) {
-                                    state.focused_on = AppFocus::Edit;
-                                } else {
-                                    state.message = Some(INFO_MSG_EDIT_EMPTY)
-                                }
+                                edit_selected_attribute(&mut state);
                             },
                             _ => {}
                         }
@@ -310,7 +644,7 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
 
         // Do other processing
         match state.focused_on {
-            AppFocus::Elements => {
+            AppFocus::Elements | AppFocus::Search | AppFocus::Calendar => {
                 if state.details_state.selected().is_some() {
                     state.details_state.select(None);
                 }
@@ -325,14 +659,39 @@ async fn run_app<'t, B: Backend>(terminal: &'t mut Terminal<B>, cfg: AppConfig)
                     state.set_edit(Some("".to_string()));
                 }
             },
+            AppFocus::Conflicts => {
+                if state.get_conflicts().is_empty() {
+                    state.focused_on = AppFocus::Elements;
+                } else if state.conflict_state.selected().is_none() {
+                    state.conflict_state.select(Some(0));
+                }
+            },
         }
-    }
+    };
+
+    engine::write_cache(&state, REGISTRY_CACHE_NAME);
+    engine::write_alerts(&mut state);
+
+    result
 }
 
 #[tokio::main]
 async fn main() -> Result<(), io::Error> {
     // Obtain Config
-    let config: AppConfig = engine::init();
+    let config: AppConfig = engine::init().await;
+
+    if config.daemon {
+        engine::run_daemon(config).await;
+        return Ok(());
+    }
+
+    // Restore the terminal before reporting a panic
+    let default_hook = std::panic::take_hook();
+    std::panic::set_hook(Box::new(move |info| {
+        disable_raw_mode().ok();
+        execute!(io::stdout(), LeaveAlternateScreen, DisableMouseCapture).ok();
+        default_hook(info);
+    }));
 
     // Set up Terminal
     enable_raw_mode()?;